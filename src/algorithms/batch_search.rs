@@ -0,0 +1,123 @@
+//! adaptive search of many queries in a sorted slice.
+use crate::prelude::*;
+use crate::traits::BasicPower;
+use std::cmp::{min, Ordering};
+use std::mem;
+
+/// Index of first element of sorted `slice` not smaller than `query`.
+fn lower_bound<T: Ord>(slice: &[T], query: &T) -> usize {
+    slice
+        .binary_search_by(|e| {
+            if e < query {
+                Ordering::Less
+            } else {
+                Ordering::Greater
+            }
+        })
+        .unwrap_err()
+}
+
+/// Same as `lower_bound` but in O(log(result)) by exponential search from the start.
+fn galloping_lower_bound<T: Ord>(slice: &[T], query: &T) -> usize {
+    let mut bound = 1;
+    while bound <= slice.len() && slice[bound - 1] < *query {
+        bound *= 2;
+    }
+    let start = bound / 2;
+    start + lower_bound(&slice[start..min(bound, slice.len())], query)
+}
+
+/// Sorted queries with the part of the haystack where their results lie.
+struct BatchSearch<'a, T: 'a> {
+    haystack: &'a [T],
+    // index of haystack's start in the whole haystack
+    offset: usize,
+    queries: &'a [T],
+    output: &'a mut [usize],
+}
+
+impl<'a, T: 'a + Ord + Sync> Divisible for BatchSearch<'a, T> {
+    type Power = BasicPower;
+    fn base_length(&self) -> usize {
+        self.queries.len()
+    }
+    fn divide(self) -> (Self, Self) {
+        // like in a merge : cut queries in the middle and the haystack where needed
+        let middle = self.queries.len() / 2;
+        let haystack_middle = lower_bound(self.haystack, &self.queries[middle]);
+        let (left_haystack, right_haystack) = self.haystack.split_at(haystack_middle);
+        let (left_queries, right_queries) = self.queries.split_at(middle);
+        let (left_output, right_output) = self.output.split_at_mut(middle);
+        (
+            BatchSearch {
+                haystack: left_haystack,
+                offset: self.offset,
+                queries: left_queries,
+                output: left_output,
+            },
+            BatchSearch {
+                haystack: right_haystack,
+                offset: self.offset + haystack_middle,
+                queries: right_queries,
+                output: right_output,
+            },
+        )
+    }
+}
+
+/// For each query, return the index of the first element of the sorted `haystack`
+/// which is not smaller than it (the index where it should be inserted).
+///
+/// Sorted queries are split together with the haystack, like in a merge,
+/// for a total work in O(n + m). Unsorted queries are searched independently.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_batch_search;
+///
+/// let boundaries: Vec<u64> = (0..100_000).map(|i| 10 * i).collect();
+/// let events: Vec<u64> = (0..200_000).map(|i| 5 * i + 1).collect();
+/// let slots = adaptive_batch_search(&boundaries, &events);
+/// assert!(slots.iter().zip(events.iter()).all(|(&s, &e)| s == (e as usize + 9) / 10));
+///
+/// let unsorted_events: Vec<u64> = events.iter().rev().cloned().collect();
+/// let slots = adaptive_batch_search(&boundaries, &unsorted_events);
+/// assert_eq!(slots[0], 100_000);
+/// ```
+pub fn adaptive_batch_search<T: Ord + Sync + Send>(haystack: &[T], queries: &[T]) -> Vec<usize> {
+    if queries.is_empty() {
+        return Vec::new();
+    }
+    let sorted = !(0..queries.len() - 1)
+        .into_adapt_iter()
+        .any(|i| queries[i] > queries[i + 1]);
+    if !sorted {
+        return queries
+            .into_adapt_iter()
+            .map(|query| lower_bound(haystack, query))
+            .collect();
+    }
+    let mut output = vec![0; queries.len()];
+    let search = BatchSearch {
+        haystack,
+        offset: 0,
+        queries,
+        output: &mut output,
+    };
+    search.partial_for_each(|mut search, limit| {
+        let output = mem::take(&mut search.output);
+        let (done, remaining) = output.split_at_mut(min(limit, output.len()));
+        let (done_queries, remaining_queries) = search.queries.split_at(done.len());
+        for (query, result) in done_queries.iter().zip(done.iter_mut()) {
+            let advance = galloping_lower_bound(search.haystack, query);
+            search.haystack = &search.haystack[advance..];
+            search.offset += advance;
+            *result = search.offset;
+        }
+        search.queries = remaining_queries;
+        search.output = remaining;
+        search
+    });
+    output
+}
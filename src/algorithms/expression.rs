@@ -0,0 +1,765 @@
+//! adaptive evaluation of arithmetic expressions.
+//! This generalizes infix solvers to `+ - * /`, unary minus, parentheses
+//! and any type of numbers.
+//! Like sequential code we apply operations from left to right so results
+//! never depend on the way the expression is divided.
+use crate::prelude::*;
+use crate::traits::BlockedPower;
+use crate::Policy;
+use std::cmp::min;
+use std::fmt;
+use std::iter::{Cloned, Zip};
+use std::ops::RangeFrom;
+use std::slice;
+
+/// Numbers expressions are evaluated on.
+/// All operations return `None` on failure (overflow, division by zero, ...).
+pub trait Number: Copy + Send + Sync {
+    fn zero() -> Self;
+    fn one() -> Self;
+    /// Parse a literal made of digits and dots.
+    fn parse(literal: &[u8]) -> Option<Self>;
+    fn checked_add(self, other: Self) -> Option<Self>;
+    fn checked_sub(self, other: Self) -> Option<Self>;
+    fn checked_mul(self, other: Self) -> Option<Self>;
+    fn checked_div(self, other: Self) -> Option<Self>;
+    fn checked_neg(self) -> Option<Self>;
+}
+
+macro_rules! implement_number_for_integer {
+    ($($t:ty),*) => {$(
+        /// Integers fail on overflows and divisions by zero.
+        /// Divisions truncate like `/` : `7 / 2 * 2` is `6`.
+        impl Number for $t {
+            fn zero() -> Self {
+                0
+            }
+            fn one() -> Self {
+                1
+            }
+            fn parse(literal: &[u8]) -> Option<Self> {
+                std::str::from_utf8(literal).ok()?.parse().ok()
+            }
+            fn checked_add(self, other: Self) -> Option<Self> {
+                <$t>::checked_add(self, other)
+            }
+            fn checked_sub(self, other: Self) -> Option<Self> {
+                <$t>::checked_sub(self, other)
+            }
+            fn checked_mul(self, other: Self) -> Option<Self> {
+                <$t>::checked_mul(self, other)
+            }
+            fn checked_div(self, other: Self) -> Option<Self> {
+                <$t>::checked_div(self, other)
+            }
+            fn checked_neg(self) -> Option<Self> {
+                <$t>::checked_neg(self)
+            }
+        }
+    )*};
+}
+
+implement_number_for_integer!(i32, i64, i128);
+
+/// Floats fail when a result is not finite.
+impl Number for f64 {
+    fn zero() -> Self {
+        0.0
+    }
+    fn one() -> Self {
+        1.0
+    }
+    fn parse(literal: &[u8]) -> Option<Self> {
+        std::str::from_utf8(literal).ok()?.parse().ok()
+    }
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(self + other).filter(|r| r.is_finite())
+    }
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        Some(self - other).filter(|r| r.is_finite())
+    }
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        Some(self * other).filter(|r| r.is_finite())
+    }
+    fn checked_div(self, other: Self) -> Option<Self> {
+        Some(self / other).filter(|r| r.is_finite())
+    }
+    fn checked_neg(self) -> Option<Self> {
+        Some(-self)
+    }
+}
+
+/// Integers modulo `M`, which must be a prime for divisions to work.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::{evaluate_seq, Modular};
+///
+/// let r: Modular<1_000_000_007> = evaluate_seq("(1000000006 + 3) * 2 / 4").unwrap();
+/// assert_eq!(r.value(), 1);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Modular<const M: u64>(u64);
+
+impl<const M: u64> Modular<M> {
+    pub fn new(value: u64) -> Self {
+        Modular(value % M)
+    }
+    pub fn value(self) -> u64 {
+        self.0
+    }
+    fn power(self, mut exponent: u64) -> Self {
+        let (mut result, mut base) = (Modular::new(1), self);
+        while exponent != 0 {
+            if exponent % 2 == 1 {
+                result =
+                    Modular((u128::from(result.0) * u128::from(base.0) % u128::from(M)) as u64);
+            }
+            base = Modular((u128::from(base.0) * u128::from(base.0) % u128::from(M)) as u64);
+            exponent /= 2;
+        }
+        result
+    }
+}
+
+impl<const M: u64> Number for Modular<M> {
+    fn zero() -> Self {
+        Modular(0)
+    }
+    fn one() -> Self {
+        Modular::new(1)
+    }
+    fn parse(literal: &[u8]) -> Option<Self> {
+        literal.iter().try_fold(Modular(0), |value, &digit| {
+            if digit.is_ascii_digit() {
+                let shifted = u128::from(value.0) * 10 + u128::from(digit - b'0');
+                Some(Modular((shifted % u128::from(M)) as u64))
+            } else {
+                None
+            }
+        })
+    }
+    fn checked_add(self, other: Self) -> Option<Self> {
+        Some(Modular(
+            ((u128::from(self.0) + u128::from(other.0)) % u128::from(M)) as u64,
+        ))
+    }
+    fn checked_sub(self, other: Self) -> Option<Self> {
+        self.checked_add(other.checked_neg()?)
+    }
+    fn checked_mul(self, other: Self) -> Option<Self> {
+        Some(Modular(
+            (u128::from(self.0) * u128::from(other.0) % u128::from(M)) as u64,
+        ))
+    }
+    fn checked_div(self, other: Self) -> Option<Self> {
+        if other.0 == 0 {
+            None
+        } else {
+            // Fermat's little theorem
+            self.checked_mul(other.power(M - 2))
+        }
+    }
+    fn checked_neg(self) -> Option<Self> {
+        Some(Modular((M - self.0) % M))
+    }
+}
+
+/// Why an expression could not be evaluated, with the position (in bytes) of the problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExpressionError {
+    InvalidCharacter(usize),
+    InvalidNumber(usize),
+    UnexpectedToken(usize),
+    UnmatchedParenthesis(usize),
+    MissingOperand(usize),
+    /// Overflow or division by zero.
+    ArithmeticError(usize),
+}
+
+impl ExpressionError {
+    pub fn position(self) -> usize {
+        match self {
+            ExpressionError::InvalidCharacter(position)
+            | ExpressionError::InvalidNumber(position)
+            | ExpressionError::UnexpectedToken(position)
+            | ExpressionError::UnmatchedParenthesis(position)
+            | ExpressionError::MissingOperand(position)
+            | ExpressionError::ArithmeticError(position) => position,
+        }
+    }
+}
+
+impl fmt::Display for ExpressionError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let problem = match self {
+            ExpressionError::InvalidCharacter(_) => "invalid character",
+            ExpressionError::InvalidNumber(_) => "invalid number",
+            ExpressionError::UnexpectedToken(_) => "unexpected token",
+            ExpressionError::UnmatchedParenthesis(_) => "unmatched parenthesis",
+            ExpressionError::MissingOperand(_) => "missing operand",
+            ExpressionError::ArithmeticError(_) => "arithmetic error",
+        };
+        write!(f, "{} at position {}", problem, self.position())
+    }
+}
+
+impl std::error::Error for ExpressionError {}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Operator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+/// An operation with its operand and the position of the operand.
+type Operation<N> = (Operator, N, usize);
+
+fn apply<N: Number>(value: N, (operator, operand, position): Operation<N>) -> Result<N, usize> {
+    match operator {
+        Operator::Add => value.checked_add(operand),
+        Operator::Sub => value.checked_sub(operand),
+        Operator::Mul => value.checked_mul(operand),
+        Operator::Div => value.checked_div(operand),
+    }
+    .ok_or(position)
+}
+
+/// Partial evaluation of a contiguous part of an expression at one parenthesis depth.
+/// Factors of each term and terms of the sum are folded from left to right.
+/// When continuing a previous part we do not know the last term and the sum we continue
+/// so operations on them are kept pending, in order, until we fuse.
+/// Failures are reported with the position of the operand.
+#[derive(Debug, Clone)]
+struct Level<N> {
+    // `*` and `/` on the last term of the level we continue, then `+` and `-` on its sum
+    pending: Vec<Operation<N>>,
+    // do we continue a level started in a previous part
+    continuing: bool,
+    // is our first term the last term of the level we continue
+    continuing_term: bool,
+    // sum of our complete terms, unless pending
+    sum: Option<N>,
+    // last term, as the operation adding it to the sum
+    term: Option<Operation<N>>,
+    // operator to apply to next factor
+    operator: Operator,
+    // is next factor negated
+    negated: bool,
+    expecting_factor: bool,
+}
+
+impl<N: Number> Level<N> {
+    /// Level starting an expression or a parenthesis.
+    fn new() -> Self {
+        Level {
+            pending: Vec::new(),
+            continuing: false,
+            continuing_term: false,
+            sum: None,
+            term: None,
+            operator: Operator::Add,
+            negated: false,
+            expecting_factor: true,
+        }
+    }
+    /// Level continuing a previous part, which ended with a factor.
+    fn continuation() -> Self {
+        Level {
+            continuing: true,
+            continuing_term: true,
+            operator: Operator::Mul,
+            expecting_factor: false,
+            ..Level::new()
+        }
+    }
+    /// Multiply or divide the last term.
+    fn push_term_factor(&mut self, operation: Operation<N>) -> Result<(), usize> {
+        if self.continuing_term {
+            self.pending.push(operation);
+        } else {
+            let term = self.term.as_mut().unwrap();
+            term.1 = apply(term.1, operation)?;
+        }
+        Ok(())
+    }
+    /// Add or subtract a complete term.
+    fn push_term(&mut self, term: Operation<N>) -> Result<(), usize> {
+        if self.continuing {
+            self.pending.push(term);
+        } else {
+            self.sum = Some(match self.sum {
+                None => term.1,
+                Some(sum) => apply(sum, term)?,
+            });
+        }
+        Ok(())
+    }
+    /// The last term is complete.
+    fn end_term(&mut self) -> Result<(), usize> {
+        if self.continuing_term {
+            self.continuing_term = false;
+        } else if let Some(term) = self.term.take() {
+            self.push_term(term)?;
+        }
+        Ok(())
+    }
+    fn push_factor(&mut self, value: N, position: usize) -> Result<(), usize> {
+        let value = if self.negated {
+            value.checked_neg().ok_or(position)?
+        } else {
+            value
+        };
+        match self.operator {
+            Operator::Mul | Operator::Div => {
+                self.push_term_factor((self.operator, value, position))?
+            }
+            Operator::Add | Operator::Sub => {
+                self.end_term()?;
+                self.term = Some((self.operator, value, position));
+            }
+        }
+        self.negated = false;
+        self.expecting_factor = false;
+        Ok(())
+    }
+    /// Continue with the level of the next part, which must continue us.
+    fn fuse(&mut self, other: Self) -> Result<(), usize> {
+        let mut ended_term = false;
+        for operation in other.pending {
+            match operation.0 {
+                Operator::Mul | Operator::Div => self.push_term_factor(operation)?,
+                Operator::Add | Operator::Sub => {
+                    if !ended_term {
+                        self.end_term()?;
+                        ended_term = true;
+                    }
+                    self.push_term(operation)?
+                }
+            }
+        }
+        if !other.continuing_term {
+            if !ended_term {
+                self.end_term()?;
+            }
+            self.term = other.term;
+        }
+        self.operator = other.operator;
+        self.negated = other.negated;
+        self.expecting_factor = other.expecting_factor;
+        Ok(())
+    }
+    /// Value of a complete level, which must not be a continuation.
+    fn evaluate(mut self) -> Result<N, usize> {
+        self.end_term()?;
+        Ok(self.sum.unwrap())
+    }
+}
+
+/// Partial evaluation of a contiguous part of an expression.
+/// We track depth with two stacks : levels closed by unmatched closing parentheses
+/// and levels opened by unmatched opening parentheses.
+#[derive(Debug, Clone)]
+struct Summary<N> {
+    // position of our first byte
+    start: Option<usize>,
+    // position of the literal being read
+    literal: Option<usize>,
+    // levels closed by unmatched parentheses, with the parentheses' positions
+    closed: Vec<(Level<N>, usize)>,
+    // levels still open, with the opening parentheses' positions, innermost last
+    open: Vec<(Level<N>, usize)>,
+    error: Option<ExpressionError>,
+}
+
+fn is_literal(byte: u8) -> bool {
+    byte.is_ascii_digit() || byte == b'.'
+}
+
+impl<N: Number> Summary<N> {
+    fn new() -> Self {
+        Summary {
+            start: None,
+            literal: None,
+            closed: Vec::new(),
+            open: Vec::new(),
+            error: None,
+        }
+    }
+    fn fail(&mut self, error: ExpressionError) {
+        if self.error.is_none() {
+            self.error = Some(error)
+        }
+    }
+    fn current_level(&mut self) -> &mut Level<N> {
+        &mut self.open.last_mut().unwrap().0
+    }
+    fn push_factor(&mut self, value: N, position: usize) {
+        let level = self.current_level();
+        if !level.expecting_factor {
+            self.fail(ExpressionError::UnexpectedToken(position))
+        } else if let Err(position) = level.push_factor(value, position) {
+            self.fail(ExpressionError::ArithmeticError(position))
+        }
+    }
+    fn push_operator(&mut self, operator: Operator, position: usize) {
+        let level = self.current_level();
+        if !level.expecting_factor {
+            level.operator = operator;
+            level.expecting_factor = true;
+        } else if operator == Operator::Sub {
+            level.negated = !level.negated;
+        } else {
+            self.fail(ExpressionError::UnexpectedToken(position))
+        }
+    }
+    fn open_parenthesis(&mut self, position: usize) {
+        if self.current_level().expecting_factor {
+            self.open.push((Level::new(), position))
+        } else {
+            self.fail(ExpressionError::UnexpectedToken(position))
+        }
+    }
+    fn close_parenthesis(&mut self, position: usize) {
+        if self.current_level().expecting_factor {
+            self.fail(ExpressionError::UnexpectedToken(position));
+            return;
+        }
+        let (level, _) = self.open.pop().unwrap();
+        if self.open.is_empty() {
+            // we might be inside a parenthesis opened before us
+            self.closed.push((level, position));
+            self.open.push((Level::continuation(), position));
+        } else {
+            match level.evaluate() {
+                Ok(value) => self.push_factor(value, position),
+                Err(position) => self.fail(ExpressionError::ArithmeticError(position)),
+            }
+        }
+    }
+    /// Take into account the literal being read.
+    fn end_literal(&mut self, bytes: &[u8]) {
+        if let Some(start) = self.literal.take() {
+            let length = bytes[start..]
+                .iter()
+                .take_while(|&&b| is_literal(b))
+                .count();
+            match N::parse(&bytes[start..start + length]) {
+                Some(value) => self.push_factor(value, start),
+                None => self.fail(ExpressionError::InvalidNumber(start)),
+            }
+        }
+    }
+    /// Advance by one byte of the expression.
+    fn push_byte(mut self, position: usize, byte: u8, bytes: &[u8]) -> Self {
+        if self.error.is_some() {
+            return self;
+        }
+        if self.start.is_none() {
+            self.start = Some(position);
+            let level = if position == 0 {
+                Level::new()
+            } else {
+                Level::continuation()
+            };
+            self.open.push((level, position));
+        }
+        if is_literal(byte) {
+            if self.literal.is_none() {
+                self.literal = Some(position);
+            }
+            return self;
+        }
+        self.end_literal(bytes);
+        match byte {
+            b'+' => self.push_operator(Operator::Add, position),
+            b'-' => self.push_operator(Operator::Sub, position),
+            b'*' => self.push_operator(Operator::Mul, position),
+            b'/' => self.push_operator(Operator::Div, position),
+            b'(' => self.open_parenthesis(position),
+            b')' => self.close_parenthesis(position),
+            b if b.is_ascii_whitespace() => (),
+            _ => self.fail(ExpressionError::InvalidCharacter(position)),
+        }
+        self
+    }
+    /// Continue with the summary of the part following us.
+    fn fuse(mut self, other: Self, bytes: &[u8]) -> Self {
+        let other_start = match other.start {
+            None => return self,
+            Some(start) => start,
+        };
+        if self.start.is_none() {
+            return other;
+        }
+        self.end_literal(bytes);
+        if self.error.is_some() {
+            return self;
+        }
+        // other starts with a binary operator or a closing parenthesis
+        if self.current_level().expecting_factor {
+            self.fail(ExpressionError::UnexpectedToken(other_start));
+            return self;
+        }
+        for (level, position) in other.closed {
+            let (mut fused, opening) = self.open.pop().unwrap();
+            if let Err(position) = fused.fuse(level) {
+                self.open.push((fused, opening));
+                self.fail(ExpressionError::ArithmeticError(position));
+                return self;
+            }
+            if self.open.is_empty() {
+                self.closed.push((fused, position));
+                self.open.push((Level::continuation(), opening));
+            } else {
+                match fused.evaluate() {
+                    Ok(value) => self.push_factor(value, position),
+                    Err(position) => self.fail(ExpressionError::ArithmeticError(position)),
+                }
+                if self.error.is_some() {
+                    return self;
+                }
+            }
+        }
+        let mut other_levels = other.open.into_iter();
+        let (first_level, _) = other_levels.next().unwrap();
+        if let Err(position) = self.current_level().fuse(first_level) {
+            self.fail(ExpressionError::ArithmeticError(position));
+            return self;
+        }
+        self.open.extend(other_levels);
+        self.literal = other.literal;
+        // pending operations come before other's error
+        if let Some(error) = other.error {
+            self.fail(error)
+        }
+        self
+    }
+    /// Final value for a summary of the whole expression.
+    fn evaluate(mut self, bytes: &[u8]) -> Result<N, ExpressionError> {
+        if self.start.is_none() {
+            return Err(ExpressionError::MissingOperand(0));
+        }
+        self.end_literal(bytes);
+        // report the first problem
+        let unmatched = self
+            .closed
+            .first()
+            .map(|&(_, position)| ExpressionError::UnmatchedParenthesis(position));
+        let error = match (unmatched, self.error) {
+            (Some(e1), Some(e2)) => Some(if e1.position() < e2.position() {
+                e1
+            } else {
+                e2
+            }),
+            (e1, e2) => e1.or(e2),
+        };
+        if let Some(error) = error {
+            return Err(error);
+        }
+        if self.open.len() > 1 {
+            return Err(ExpressionError::UnmatchedParenthesis(self.open[1].1));
+        }
+        let (level, _) = self.open.pop().unwrap();
+        if level.expecting_factor {
+            return Err(ExpressionError::MissingOperand(bytes.len()));
+        }
+        level.evaluate().map_err(ExpressionError::ArithmeticError)
+    }
+}
+
+/// Bytes of an expression, with their positions.
+/// We only divide before binary operators and closing parentheses
+/// such that all tokens and their meaning stay on one side.
+struct ExpressionBytes<'a> {
+    bytes: &'a [u8],
+    offset: usize,
+    // no possible division point, only happens when created by `divide`
+    indivisible: bool,
+}
+
+/// Can we divide `bytes` before given index ?
+/// A `-` is binary when it follows an operand, we only look for it in `bytes`.
+fn is_division_point(bytes: &[u8], index: usize) -> bool {
+    match bytes[index] {
+        b'+' | b'*' | b'/' | b')' => true,
+        b'-' => matches!(
+            bytes[..index].iter().rev().find(|b| !b.is_ascii_whitespace()),
+            Some(&b) if is_literal(b) || b == b')'
+        ),
+        _ => false,
+    }
+}
+
+impl<'a> Divisible for ExpressionBytes<'a> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        if self.indivisible {
+            min(self.bytes.len(), 1)
+        } else {
+            self.bytes.len()
+        }
+    }
+    fn divide(self) -> (Self, Self) {
+        let middle = self.bytes.len() / 2;
+        let bytes = self.bytes;
+        let point = (middle..bytes.len())
+            .find(|&i| is_division_point(bytes, i))
+            .or_else(|| (1..middle).rev().find(|&i| is_division_point(bytes, i)));
+        match point {
+            Some(point) => self.divide_at(point),
+            None => {
+                let offset = self.offset + bytes.len();
+                (
+                    ExpressionBytes {
+                        indivisible: true,
+                        ..self
+                    },
+                    ExpressionBytes {
+                        bytes: &[],
+                        offset,
+                        indivisible: false,
+                    },
+                )
+            }
+        }
+    }
+}
+
+impl<'a> DivisibleIntoBlocks for ExpressionBytes<'a> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let index = min(index, self.bytes.len());
+        // we can always leave nothing on the left
+        let point = (index..self.bytes.len())
+            .find(|&i| i == 0 || is_division_point(self.bytes, i))
+            .unwrap_or(self.bytes.len());
+        let (left, right) = self.bytes.split_at(point);
+        (
+            ExpressionBytes {
+                bytes: left,
+                offset: self.offset,
+                indivisible: self.indivisible,
+            },
+            ExpressionBytes {
+                bytes: right,
+                offset: self.offset + point,
+                indivisible: false,
+            },
+        )
+    }
+}
+
+impl<'a> IntoIterator for ExpressionBytes<'a> {
+    type Item = (usize, u8);
+    type IntoIter = Zip<RangeFrom<usize>, Cloned<slice::Iter<'a, u8>>>;
+    fn into_iter(self) -> Self::IntoIter {
+        (self.offset..).zip(self.bytes.iter().cloned())
+    }
+}
+
+impl<'a> AdaptiveIterator for ExpressionBytes<'a> {}
+
+fn expression_bytes(expression: &str) -> ExpressionBytes<'_> {
+    ExpressionBytes {
+        bytes: expression.as_bytes(),
+        offset: 0,
+        indivisible: false,
+    }
+}
+
+/// Evaluate given expression sequentially.
+/// Expressions contain numbers, `+ - * /`, unary minus and parentheses.
+/// Usual precedence rules apply.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::{evaluate_seq, ExpressionError};
+///
+/// assert_eq!(evaluate_seq::<i64>("2 * (3 + 4) - -6 / 3"), Ok(16));
+/// assert_eq!(evaluate_seq::<f64>("1.5 * (2 - 4)"), Ok(-3.0));
+/// assert_eq!(evaluate_seq::<i64>("7 / 2 - 9 / -4"), Ok(5));
+/// assert_eq!(
+///     evaluate_seq::<i64>("2 * (3 + 4"),
+///     Err(ExpressionError::UnmatchedParenthesis(4))
+/// );
+/// assert_eq!(
+///     evaluate_seq::<i32>("65536 * 65536"),
+///     Err(ExpressionError::ArithmeticError(8))
+/// );
+/// ```
+pub fn evaluate_seq<N: Number>(expression: &str) -> Result<N, ExpressionError> {
+    let bytes = expression.as_bytes();
+    expression_bytes(expression)
+        .into_iter()
+        .fold(Summary::new(), |summary, (position, byte)| {
+            summary.push_byte(position, byte, bytes)
+        })
+        .evaluate(bytes)
+}
+
+/// Evaluate given expression in parallel with given scheduling policy.
+/// Each task folds its blocks into partial evaluations which are fused while reducing.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::{evaluate_adaptive, evaluate_seq, Modular, Policy};
+///
+/// let expression = "(1 + 2 * -3) * (4 - 5) / 7 + ".repeat(100_000) + "1";
+/// let result: Modular<1_000_000_007> = evaluate_adaptive(&expression, Policy::DefaultPolicy).unwrap();
+/// assert_eq!(result, evaluate_seq(&expression).unwrap());
+///
+/// // only the sequential evaluation order matters, not the way we divide
+/// let expression = "-1 - 1 + 0 * 9999999999 * 9999999999 / 2 ".repeat(10_000);
+/// assert_eq!(evaluate_adaptive::<i64>(&expression, Policy::Join(1)), Ok(-20_000));
+/// ```
+pub fn evaluate_adaptive<N: Number>(
+    expression: &str,
+    policy: Policy,
+) -> Result<N, ExpressionError> {
+    if expression.is_empty() {
+        return Err(ExpressionError::MissingOperand(0));
+    }
+    let bytes = expression.as_bytes();
+    expression_bytes(expression)
+        .with_policy(policy)
+        .fold(Summary::new, |summary, (position, byte)| {
+            summary.push_byte(position, byte, bytes)
+        })
+        .reduce(|left, right| left.fuse(right, bytes))
+        .evaluate(bytes)
+}
+
+/// Evaluate given expression in parallel.
+/// The main thread evaluates sequentially while others help it by evaluating
+/// parts of the expression it has not reached yet.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::{evaluate_fully_adaptive, evaluate_seq};
+///
+/// let expression = "((3 - 1) * 2 + -1) * 4 / 2 - ".repeat(100_000) + "3";
+/// let result: i64 = evaluate_fully_adaptive(&expression).unwrap();
+/// assert_eq!(result, evaluate_seq(&expression).unwrap());
+/// ```
+pub fn evaluate_fully_adaptive<N: Number>(expression: &str) -> Result<N, ExpressionError> {
+    if expression.is_empty() {
+        return Err(ExpressionError::MissingOperand(0));
+    }
+    let bytes = expression.as_bytes();
+    expression_bytes(expression)
+        .fold(Summary::new, |summary, (position, byte)| {
+            summary.push_byte(position, byte, bytes)
+        })
+        .helping_fold(
+            Summary::new(),
+            |summary, (position, byte)| summary.push_byte(position, byte, bytes),
+            |summary, helped| summary.fuse(helped, bytes),
+        )
+        .evaluate(bytes)
+}
@@ -0,0 +1,205 @@
+//! adaptive runs of finite state machines on bytes.
+use crate::prelude::*;
+use smallvec::SmallVec;
+use std::ops::Range;
+
+/// For each start state, the state reached after a part of the input.
+#[derive(Debug, Clone)]
+struct StateMapping {
+    targets: SmallVec<[u8; 16]>,
+}
+
+impl StateMapping {
+    /// Mapping of an empty input.
+    fn identity(states: usize) -> Self {
+        StateMapping {
+            targets: (0..states).map(|state| state as u8).collect(),
+        }
+    }
+    /// Extend ourselves by running given bytes from all reached states.
+    fn run(&mut self, bytes: &[u8], table: &[[u8; 256]]) {
+        for target in self.targets.iter_mut() {
+            *target = run_from(*target, bytes, table);
+        }
+    }
+    /// Mapping of our part of the input followed by the one of `other`.
+    fn fuse(mut self, other: &Self) -> Self {
+        for target in self.targets.iter_mut() {
+            *target = other.targets[*target as usize];
+        }
+        self
+    }
+    fn apply(&self, state: u8) -> u8 {
+        self.targets[state as usize]
+    }
+}
+
+/// Sequentially run given bytes starting from given state.
+fn run_from(state: u8, bytes: &[u8], table: &[[u8; 256]]) -> u8 {
+    bytes
+        .iter()
+        .fold(state, |state, &byte| table[state as usize][byte as usize])
+}
+
+/// Return the state reached after running the whole input from `start`.
+/// `table[state][byte]` is the state following `state` when reading `byte`.
+/// There can be at most 256 states.
+///
+/// Each block computes the state reached for all possible start states
+/// and these mappings are composed while reducing.
+/// Work is therefore multiplied by the number of states.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_fsm_final_state;
+///
+/// // are we inside a quoted string ?
+/// let mut table = [[0u8; 256]; 2];
+/// table[1] = [1; 256];
+/// table[0][b'"' as usize] = 1;
+/// table[1][b'"' as usize] = 0;
+/// let csv: Vec<u8> = "a,\"b,c\"\n".repeat(10_000).into_bytes();
+/// assert_eq!(adaptive_fsm_final_state(&csv, &table, 0), 0);
+/// assert_eq!(adaptive_fsm_final_state(&csv[..csv.len() - 3], &table, 0), 1);
+/// ```
+pub fn adaptive_fsm_final_state(bytes: &[u8], table: &[[u8; 256]], start: u8) -> u8 {
+    assert!(table.len() <= 256);
+    if bytes.is_empty() {
+        return start;
+    }
+    bytes
+        .partial_fold(
+            || StateMapping::identity(table.len()),
+            |mut mapping, bytes, limit| {
+                let (todo, remaining) = bytes.divide_at(limit);
+                mapping.run(todo, table);
+                (mapping, remaining)
+            },
+        )
+        .reduce(|left, right| left.fuse(&right))
+        .apply(start)
+}
+
+/// Raw pointer on the states we can share between threads.
+/// Each part of the input writes to different positions.
+#[derive(Clone, Copy)]
+struct SharedStates(*mut u8);
+
+unsafe impl Send for SharedStates {}
+unsafe impl Sync for SharedStates {}
+
+/// What we know about a part of the input while running it.
+enum RunPart {
+    Empty,
+    /// The part starts where the state is known : all its states are written
+    /// and we know the state at its end.
+    Written(u8),
+    /// The part starts at an unknown state : we know the mapping of all start states.
+    Mapped(Range<usize>, StateMapping),
+}
+
+/// Write the states reached after each byte of `range`, starting from `start`,
+/// and return the state at the end of the range.
+/// Parts starting where the state is unknown are run again from their real start state
+/// once the state before them is known.
+fn run_range(
+    bytes: &[u8],
+    states: SharedStates,
+    range: Range<usize>,
+    table: &[[u8; 256]],
+    start: u8,
+) -> u8 {
+    let first = range.start;
+    let write_states = |todo: Range<usize>, mut state: u8| {
+        for index in todo {
+            state = table[state as usize][bytes[index] as usize];
+            unsafe { *states.0.add(index) = state };
+        }
+        state
+    };
+    let part = range
+        .partial_fold(
+            || RunPart::Empty,
+            |part, range, limit| {
+                let (todo, remaining) = range.divide_at(limit);
+                let part = match part {
+                    RunPart::Empty if todo.start == first => {
+                        RunPart::Written(write_states(todo, start))
+                    }
+                    RunPart::Empty => {
+                        let mut mapping = StateMapping::identity(table.len());
+                        mapping.run(&bytes[todo.clone()], table);
+                        RunPart::Mapped(todo, mapping)
+                    }
+                    RunPart::Written(state) => RunPart::Written(write_states(todo, state)),
+                    RunPart::Mapped(mapped, mut mapping) => {
+                        mapping.run(&bytes[todo.clone()], table);
+                        RunPart::Mapped(mapped.start..todo.end, mapping)
+                    }
+                };
+                (part, remaining)
+            },
+        )
+        .reduce(|left, right| match (left, right) {
+            (RunPart::Empty, part) | (part, RunPart::Empty) => part,
+            (RunPart::Written(state), RunPart::Mapped(mapped, _)) => {
+                RunPart::Written(run_range(bytes, states, mapped, table, state))
+            }
+            (
+                RunPart::Mapped(left_range, left_mapping),
+                RunPart::Mapped(right_range, right_mapping),
+            ) => RunPart::Mapped(
+                left_range.start..right_range.end,
+                left_mapping.fuse(&right_mapping),
+            ),
+            (_, RunPart::Written(_)) => unreachable!("only the first part starts at a known state"),
+        });
+    match part {
+        RunPart::Written(state) => state,
+        _ => unreachable!("the first part starts at a known state"),
+    }
+}
+
+/// Return the state reached after each byte when running the input from `start`.
+/// `table[state][byte]` is the state following `state` when reading `byte`.
+/// There can be at most 256 states.
+///
+/// The task running the start of the input knows its start state and directly writes states.
+/// Other tasks compute the mapping of all start states for their part, like
+/// `adaptive_fsm_final_state`, and these mappings are composed while reducing.
+/// Once the state before a part is known, the part is run again (adaptively)
+/// from its real start state.
+/// Without any steal there is no overhead.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_fsm_run;
+///
+/// // are we inside a quoted string ?
+/// let mut table = [[0u8; 256]; 2];
+/// table[1] = [1; 256];
+/// table[0][b'"' as usize] = 1;
+/// table[1][b'"' as usize] = 0;
+/// let csv: Vec<u8> = "a,\"b,c\"\n".repeat(100_000).into_bytes();
+/// let states = adaptive_fsm_run(&csv, &table, 0);
+/// // record boundaries are newlines outside of quotes
+/// let records = csv
+///     .iter()
+///     .zip(states.iter())
+///     .filter(|&(&byte, &state)| byte == b'\n' && state == 0)
+///     .count();
+/// assert_eq!(records, 100_000);
+/// assert_eq!(&states[..8], &[0, 0, 1, 1, 1, 1, 0, 0]);
+/// ```
+pub fn adaptive_fsm_run(bytes: &[u8], table: &[[u8; 256]], start: u8) -> Vec<u8> {
+    assert!(table.len() <= 256);
+    if bytes.is_empty() {
+        return Vec::new();
+    }
+    let mut states = vec![0; bytes.len()];
+    let shared_states = SharedStates(states.as_mut_ptr());
+    run_range(bytes, shared_states, 0..bytes.len(), table, start);
+    states
+}
@@ -0,0 +1,150 @@
+//! adaptive reductions by key.
+use crate::prelude::*;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Reduced runs of equal keys for a contiguous part of the input.
+/// Only the first and last runs can be incomplete.
+#[derive(Debug, Clone)]
+struct Runs<K, V> {
+    runs: Vec<(K, V)>,
+}
+
+impl<K: PartialEq, V> Runs<K, V> {
+    fn new() -> Self {
+        Runs { runs: Vec::new() }
+    }
+    /// Add next key and value, reducing with the last run if keys are equal.
+    fn push<O: Fn(V, V) -> V>(&mut self, key: K, value: V, op: &O) {
+        match self.runs.pop() {
+            Some((last_key, last_value)) => {
+                if last_key == key {
+                    self.runs.push((last_key, op(last_value, value)));
+                } else {
+                    self.runs.push((last_key, last_value));
+                    self.runs.push((key, value));
+                }
+            }
+            None => self.runs.push((key, value)),
+        }
+    }
+    /// Fuse with the runs of the next part of the input.
+    /// A run straddling the boundary is reduced into one.
+    fn fuse<O: Fn(V, V) -> V>(mut self, other: Self, op: &O) -> Self {
+        let mut other_runs = other.runs.into_iter();
+        if let Some((key, value)) = other_runs.next() {
+            self.push(key, value, op);
+        }
+        self.runs.extend(other_runs);
+        self
+    }
+}
+
+/// Reduce with `op` the values of each run of consecutive equal keys.
+/// Return for each run its key and its reduced value, in order.
+/// If keys are sorted this is a reduction for each distinct key.
+/// `op` must be associative.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_reduce_by_key;
+///
+/// let keys: Vec<u32> = (0..100_000).map(|i| i / 1000).collect();
+/// let values: Vec<u64> = (0..100_000).collect();
+/// let sums = adaptive_reduce_by_key(&keys, &values, |a, b| a + b);
+/// assert_eq!(sums.len(), 100);
+/// assert!(sums
+///     .iter()
+///     .all(|&(k, s)| s == (1000 * k as u64..1000 * (k as u64 + 1)).sum::<u64>()));
+/// ```
+pub fn adaptive_reduce_by_key<K, V, O>(keys: &[K], values: &[V], op: O) -> Vec<(K, V)>
+where
+    K: PartialEq + Clone + Send + Sync,
+    V: Clone + Send + Sync,
+    O: Fn(V, V) -> V + Sync + Send,
+{
+    assert_eq!(keys.len(), values.len());
+    if keys.is_empty() {
+        return Vec::new();
+    }
+    let op_ref = &op;
+    keys.into_adapt_iter()
+        .zip(values.into_adapt_iter())
+        .fold(Runs::new, |mut runs, (key, value)| {
+            runs.push(key.clone(), value.clone(), op_ref);
+            runs
+        })
+        .reduce(|left, right| left.fuse(right, op_ref))
+        .runs
+}
+
+/// Group all elements by key, folding the elements of each group.
+/// Each task folds into its own `HashMap` and maps are then merged together,
+/// preserving the order of groups for `merge`.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::group_by_fold;
+/// use rayon_adaptive::prelude::*;
+///
+/// let words = vec!["adaptive", "rayon", "fold", "adapt", "reduce", "fast"];
+/// let counts = group_by_fold(
+///     words.into_adapt_iter(),
+///     |w| w.chars().next().unwrap(),
+///     || 0,
+///     |count, _| count + 1,
+///     |c1, c2| c1 + c2,
+/// );
+/// assert_eq!(counts[&'a'], 2);
+/// assert_eq!(counts[&'r'], 2);
+/// assert_eq!(counts[&'f'], 2);
+/// ```
+pub fn group_by_fold<I, K, A, KF, ID, F, M>(
+    iter: I,
+    key_fn: KF,
+    init: ID,
+    fold: F,
+    merge: M,
+) -> HashMap<K, A>
+where
+    I: AdaptiveIterator,
+    K: Eq + Hash + Send + Sync,
+    A: Send + Sync,
+    KF: Fn(&I::Item) -> K + Sync,
+    ID: Fn() -> A + Sync,
+    F: Fn(A, I::Item) -> A + Sync,
+    M: Fn(A, A) -> A + Sync,
+{
+    if iter.base_length() == 0 {
+        return HashMap::new();
+    }
+    iter.partial_fold(HashMap::new, |mut groups, i, limit| {
+        let (todo, remaining) = i.divide_at(limit);
+        for item in todo {
+            let key = key_fn(&item);
+            let group = groups.remove(&key).unwrap_or_else(&init);
+            groups.insert(key, fold(group, item));
+        }
+        (groups, remaining)
+    })
+    .reduce(|groups1, groups2| {
+        // we merge the small map into the large one but keep groups in order
+        let swapped = groups1.len() < groups2.len();
+        let (mut large_groups, small_groups) = if swapped {
+            (groups2, groups1)
+        } else {
+            (groups1, groups2)
+        };
+        for (key, group) in small_groups {
+            let merged = match large_groups.remove(&key) {
+                Some(large_group) if swapped => merge(group, large_group),
+                Some(large_group) => merge(large_group, group),
+                None => group,
+            };
+            large_groups.insert(key, merged);
+        }
+        large_groups
+    })
+}
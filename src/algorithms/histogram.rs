@@ -0,0 +1,62 @@
+//! adaptive histograms.
+use crate::prelude::*;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Above this number of bins, private histograms do not fit in cache anymore.
+const MAX_PRIVATE_BINS: usize = 1 << 16;
+
+/// Count for each bin the number of elements falling into it.
+/// `bin_fn` returns for each element the index of its bin (which must be less than `bins`).
+///
+/// For small bin counts each task fills its own histogram, reused across all its blocks,
+/// and histograms are added while reducing.
+/// For large bin counts all tasks increment a shared histogram of atomic counters.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_histogram;
+/// use rayon_adaptive::prelude::*;
+///
+/// let v: Vec<u32> = (0..100_000).collect();
+/// let histogram = adaptive_histogram(v.into_adapt_iter(), 10, |&e| (e % 10) as usize);
+/// assert_eq!(histogram, vec![10_000; 10]);
+/// let histogram = adaptive_histogram(v.into_adapt_iter(), 1 << 20, |&e| e as usize);
+/// assert!(histogram[..100_000].iter().all(|&c| c == 1));
+/// ```
+pub fn adaptive_histogram<I, B>(iter: I, bins: usize, bin_fn: B) -> Vec<usize>
+where
+    I: AdaptiveIterator,
+    B: Fn(I::Item) -> usize + Sync + Send,
+{
+    let len = iter.base_length();
+    if len == 0 {
+        return vec![0; bins];
+    }
+    if bins > MAX_PRIVATE_BINS || bins * rayon::current_num_threads() > len {
+        // adding private histograms would cost more than counting
+        let histogram: Vec<AtomicUsize> = (0..bins).map(|_| AtomicUsize::new(0)).collect();
+        iter.for_each(|e| {
+            histogram[bin_fn(e)].fetch_add(1, Ordering::Relaxed);
+        });
+        histogram.into_iter().map(AtomicUsize::into_inner).collect()
+    } else {
+        iter.partial_fold(
+            || vec![0; bins],
+            |mut histogram, i, limit| {
+                let (todo, remaining) = i.divide_at(limit);
+                for e in todo {
+                    histogram[bin_fn(e)] += 1;
+                }
+                (histogram, remaining)
+            },
+        )
+        .reduce(|mut histogram1, histogram2| {
+            histogram1
+                .iter_mut()
+                .zip(histogram2.iter())
+                .for_each(|(c1, c2)| *c1 += c2);
+            histogram1
+        })
+    }
+}
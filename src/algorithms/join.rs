@@ -0,0 +1,297 @@
+//! adaptive relational joins.
+use super::batch_search::adaptive_batch_search;
+use super::group_by::group_by_fold;
+use super::merge_sort::adaptive_sort_by_key;
+use super::prefix::adaptive_prefix;
+use crate::prelude::*;
+use crate::traits::BlockedPower;
+use std::cmp::{min, Ordering};
+use std::hash::Hash;
+use std::slice;
+
+/// Elements of both sides matching for a given key.
+type Group<'g, 'a, L, R> = (&'g [&'a L], &'g [&'a R]);
+
+/// Parallel iterator on a range of the pairs of all groups.
+/// Pairs of a group are numbered row by row.
+/// Since we divide on pairs and not on groups, heavy keys are shared among tasks.
+struct JoinedPairs<'g, 'a: 'g, L: 'a, R: 'a> {
+    groups: &'g [Group<'g, 'a, L, R>],
+    // number of pairs in all groups up to each group (included)
+    ends: &'g [usize],
+    start: usize,
+    end: usize,
+}
+
+impl<'g, 'a: 'g, L: 'a + Sync, R: 'a + Sync> Divisible for JoinedPairs<'g, 'a, L, R> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        self.end - self.start
+    }
+    fn divide(self) -> (Self, Self) {
+        let middle = self.base_length() / 2;
+        self.divide_at(middle)
+    }
+}
+
+impl<'g, 'a: 'g, L: 'a + Sync, R: 'a + Sync> DivisibleIntoBlocks for JoinedPairs<'g, 'a, L, R> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let middle = min(self.start + index, self.end);
+        (
+            JoinedPairs {
+                groups: self.groups,
+                ends: self.ends,
+                start: self.start,
+                end: middle,
+            },
+            JoinedPairs {
+                groups: self.groups,
+                ends: self.ends,
+                start: middle,
+                end: self.end,
+            },
+        )
+    }
+}
+
+/// Sequential iterator on a range of the pairs of all groups.
+struct JoinedPairsIter<'g, 'a: 'g, L: 'a, R: 'a> {
+    groups: &'g [Group<'g, 'a, L, R>],
+    ends: &'g [usize],
+    group: usize,
+    pair: usize,
+    end: usize,
+}
+
+impl<'g, 'a: 'g, L: 'a, R: 'a> Iterator for JoinedPairsIter<'g, 'a, L, R> {
+    type Item = (&'a L, &'a R);
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.pair == self.end {
+            return None;
+        }
+        while self.ends[self.group] <= self.pair {
+            self.group += 1;
+        }
+        let (left, right) = self.groups[self.group];
+        let index = self.pair + left.len() * right.len() - self.ends[self.group];
+        self.pair += 1;
+        Some((left[index / right.len()], right[index % right.len()]))
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.end - self.pair;
+        (remaining, Some(remaining))
+    }
+}
+
+impl<'g, 'a: 'g, L: 'a, R: 'a> IntoIterator for JoinedPairs<'g, 'a, L, R> {
+    type Item = (&'a L, &'a R);
+    type IntoIter = JoinedPairsIter<'g, 'a, L, R>;
+    fn into_iter(self) -> Self::IntoIter {
+        // first group ending after our start
+        let start = self.start;
+        let group = self
+            .ends
+            .binary_search_by(|&end| {
+                if end <= start {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            })
+            .unwrap_err();
+        JoinedPairsIter {
+            groups: self.groups,
+            ends: self.ends,
+            group,
+            pair: self.start,
+            end: self.end,
+        }
+    }
+}
+
+impl<'g, 'a: 'g, L: 'a + Sync, R: 'a + Sync> AdaptiveIterator for JoinedPairs<'g, 'a, L, R> {}
+
+/// Return all pairs of all groups.
+fn expand_groups<'g, 'a, L, R>(groups: &'g [Group<'g, 'a, L, R>]) -> Vec<(&'a L, &'a R)>
+where
+    L: Sync,
+    R: Sync,
+{
+    if groups.is_empty() {
+        return Vec::new();
+    }
+    let mut ends: Vec<usize> = groups
+        .into_adapt_iter()
+        .map(|(left, right)| left.len() * right.len())
+        .collect();
+    adaptive_prefix(&mut ends, |a, b| a + b);
+    JoinedPairs {
+        groups,
+        ends: &ends,
+        start: 0,
+        end: ends[ends.len() - 1],
+    }
+    .collect()
+}
+
+/// Number of elements at the start of sorted `keys` which are equal to `key`.
+/// Exponential search keeps it in O(log(result)).
+fn equal_prefix_length<K: Ord>(keys: &[K], key: &K) -> usize {
+    let mut bound = 1;
+    while bound <= keys.len() && keys[bound - 1] == *key {
+        bound *= 2;
+    }
+    let start = bound / 2;
+    start
+        + keys[start..min(bound, keys.len())]
+            .binary_search_by(|k| {
+                if k <= key {
+                    Ordering::Less
+                } else {
+                    Ordering::Greater
+                }
+            })
+            .unwrap_err()
+}
+
+/// Return all pairs of elements of `left` and `right` with equal keys.
+/// Pairs are ordered by key, then by position in `left`, then by position in `right`.
+///
+/// Both sides are sorted by key (as references) and matching runs of equal keys
+/// are found with a merge-like batch search.
+/// Pairs are then generated in parallel, even inside one very frequent key.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_sort_merge_join;
+///
+/// let orders: Vec<(u32, u32)> = (0..100_000).map(|i| (i, i % 1000)).collect();
+/// let customers: Vec<(u32, String)> = (0..500).rev().map(|c| (2 * c, c.to_string())).collect();
+/// let joined = adaptive_sort_merge_join(&orders, &customers, |o| o.1, |c| c.0);
+/// assert_eq!(joined.len(), 50_000);
+/// assert!(joined.iter().all(|(o, c)| o.1 == c.0));
+/// assert_eq!(joined[0].1 .1, "0");
+/// assert_eq!(joined[49_999].1 .1, "499");
+/// ```
+pub fn adaptive_sort_merge_join<'a, L, R, K, KL, KR>(
+    left: &'a [L],
+    right: &'a [R],
+    key_l: KL,
+    key_r: KR,
+) -> Vec<(&'a L, &'a R)>
+where
+    L: Sync,
+    R: Sync,
+    K: Ord + Send + Sync,
+    KL: Fn(&L) -> K + Sync,
+    KR: Fn(&R) -> K + Sync,
+{
+    if left.is_empty() || right.is_empty() {
+        return Vec::new();
+    }
+    let mut sorted_left: Vec<&L> = left.into_adapt_iter().collect();
+    let mut sorted_right: Vec<&R> = right.into_adapt_iter().collect();
+    adaptive_sort_by_key(&mut sorted_left, |l| key_l(l));
+    adaptive_sort_by_key(&mut sorted_right, |r| key_r(r));
+    let (sorted_left, sorted_right) = (&sorted_left, &sorted_right);
+    let key_l = &key_l;
+    // start of each run of equal keys on the left side
+    let run_starts: Vec<usize> = (0..sorted_left.len())
+        .into_adapt_iter()
+        .filter(|&i| i == 0 || key_l(sorted_left[i - 1]) != key_l(sorted_left[i]))
+        .collect();
+    let run_keys: Vec<K> = run_starts
+        .into_adapt_iter()
+        .map(|&start| key_l(sorted_left[start]))
+        .collect();
+    let right_keys: Vec<K> = sorted_right.into_adapt_iter().map(|r| key_r(r)).collect();
+    // run keys are sorted so they are all searched in one merge
+    let right_starts = adaptive_batch_search(&right_keys, &run_keys);
+    let (run_starts, run_keys, right_keys, right_starts) =
+        (&run_starts, &run_keys, &right_keys, &right_starts);
+    let groups: Vec<Group<L, R>> = (0..run_starts.len())
+        .into_adapt_iter()
+        .filter(|&run| {
+            let right_start = right_starts[run];
+            right_start < right_keys.len() && right_keys[right_start] == run_keys[run]
+        })
+        .map(|run| {
+            let left_end = run_starts
+                .get(run + 1)
+                .cloned()
+                .unwrap_or(sorted_left.len());
+            let right_start = right_starts[run];
+            let right_end =
+                right_start + equal_prefix_length(&right_keys[right_start..], &run_keys[run]);
+            (
+                &sorted_left[run_starts[run]..left_end],
+                &sorted_right[right_start..right_end],
+            )
+        })
+        .collect();
+    expand_groups(&groups)
+}
+
+/// Return all pairs of elements of `left` and `right` with equal keys.
+/// Pairs are ordered by position in `right`, then by position in `left`.
+///
+/// A hash table of `left` is built in parallel and probed by all elements of `right`.
+/// Pairs are then generated in parallel, even for elements matching a very frequent key.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_hash_join;
+///
+/// let orders: Vec<(u32, u32)> = (0..100_000).map(|i| (i, i % 1000)).collect();
+/// let customers: Vec<(u32, String)> = (0..500).rev().map(|c| (2 * c, c.to_string())).collect();
+/// let joined = adaptive_hash_join(&orders, &customers, |o| o.1, |c| c.0);
+/// assert_eq!(joined.len(), 50_000);
+/// assert!(joined.iter().all(|(o, c)| o.1 == c.0));
+/// assert_eq!(joined[0].1 .1, "499");
+/// assert_eq!(joined[0].0 .0, 998);
+/// ```
+pub fn adaptive_hash_join<'a, L, R, K, KL, KR>(
+    left: &'a [L],
+    right: &'a [R],
+    key_l: KL,
+    key_r: KR,
+) -> Vec<(&'a L, &'a R)>
+where
+    L: Sync,
+    R: Sync,
+    K: Eq + Hash + Send + Sync,
+    KL: Fn(&L) -> K + Sync,
+    KR: Fn(&R) -> K + Sync,
+{
+    if left.is_empty() || right.is_empty() {
+        return Vec::new();
+    }
+    let table = group_by_fold(
+        left.into_adapt_iter(),
+        |l| key_l(l),
+        Vec::new,
+        |mut matches, l| {
+            matches.push(l);
+            matches
+        },
+        |mut matches1, matches2| {
+            matches1.extend(matches2);
+            matches1
+        },
+    );
+    let right_refs: Vec<&R> = right.into_adapt_iter().collect();
+    let (table, key_r) = (&table, &key_r);
+    let groups: Vec<Group<L, R>> = right_refs
+        .into_adapt_iter()
+        .map(|r| {
+            table
+                .get(&key_r(r))
+                .map(|matches| (matches.as_slice(), slice::from_ref(r)))
+        })
+        .filter(Option::is_some)
+        .map(Option::unwrap)
+        .collect();
+    expand_groups(&groups)
+}
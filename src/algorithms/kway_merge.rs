@@ -0,0 +1,133 @@
+//! adaptive parallel merge of many sorted runs at once.
+use crate::prelude::*;
+use crate::traits::BasicPower;
+use std::cmp::{min, Ordering, Reverse};
+use std::collections::BinaryHeap;
+use std::mem;
+
+/// Number of elements of `run` smaller than `x` (or smaller or equal if `or_equal`).
+fn count_before<T: Ord>(run: &[T], x: &T, or_equal: bool) -> usize {
+    run.binary_search_by(|e| match e.cmp(x) {
+        Ordering::Equal if or_equal => Ordering::Less,
+        Ordering::Equal => Ordering::Greater,
+        o => o,
+    })
+    .unwrap_err()
+}
+
+/// Split positions in all runs around a splitter element, like the two-way merge split.
+/// The splitter is the middle element of the largest run
+/// (or the median head if all runs have only one element),
+/// and we find in each run how many elements go before it in the merged output
+/// with one binary search : O(k log(n)).
+/// On equal elements, elements of first runs come first.
+fn split_positions<T: Ord>(runs: &[&[T]]) -> Vec<usize> {
+    let (index, position) = match runs.iter().enumerate().max_by_key(|(_, run)| run.len()) {
+        Some((index, run)) if run.len() > 1 => (index, run.len() / 2),
+        _ => {
+            let mut heads: Vec<(&T, usize)> = runs.iter().map(|run| &run[0]).zip(0..).collect();
+            heads.sort();
+            (heads[heads.len() / 2].1, 0)
+        }
+    };
+    let splitter = &runs[index][position];
+    runs.iter()
+        .enumerate()
+        .map(|(i, run)| match i.cmp(&index) {
+            Ordering::Less => count_before(run, splitter, true),
+            Ordering::Equal => position,
+            Ordering::Greater => count_before(run, splitter, false),
+        })
+        .collect()
+}
+
+/// Merge sequentially the first elements of `runs` into `output`,
+/// advancing runs past the elements used.
+fn sequential_merge<'a, T: Ord + Copy>(runs: &mut [&'a [T]], output: &mut [T]) {
+    let mut heads: BinaryHeap<Reverse<(&'a T, usize)>> = runs
+        .iter()
+        .copied()
+        .enumerate()
+        .filter_map(|(index, run)| run.first().map(|head| Reverse((head, index))))
+        .collect();
+    for o in output {
+        let Reverse((head, index)) = heads.pop().unwrap();
+        *o = *head;
+        let run = &runs[index][1..];
+        runs[index] = run;
+        if let Some(next_head) = run.first() {
+            heads.push(Reverse((next_head, index)));
+        }
+    }
+}
+
+struct KwayMerge<'a, T: 'a> {
+    runs: Vec<&'a [T]>,
+    output: &'a mut [T],
+}
+
+impl<'a, T: 'a + Ord + Copy + Send + Sync> Divisible for KwayMerge<'a, T> {
+    type Power = BasicPower;
+    fn base_length(&self) -> usize {
+        self.output.len()
+    }
+    fn divide(self) -> (Self, Self) {
+        let splits = split_positions(&self.runs);
+        let middle = splits.iter().sum();
+        let (left_runs, right_runs): (Vec<_>, Vec<_>) = self
+            .runs
+            .iter()
+            .zip(splits)
+            .map(|(run, split)| run.split_at(split))
+            .unzip();
+        let (left_output, right_output) = self.output.split_at_mut(middle);
+        (
+            KwayMerge {
+                runs: left_runs.into_iter().filter(|r| !r.is_empty()).collect(),
+                output: left_output,
+            },
+            KwayMerge {
+                runs: right_runs.into_iter().filter(|r| !r.is_empty()).collect(),
+                output: right_output,
+            },
+        )
+    }
+}
+
+/// Merge all given sorted runs into `output` at once.
+/// `output` must be exactly as large as all runs together.
+/// Output is split among tasks around a splitter element we locate in all runs
+/// by binary searches.
+/// Merge is stable : on equal elements, elements of first runs come first.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_kway_merge;
+///
+/// let runs: Vec<Vec<u32>> = (0..32)
+///     .map(|r| (0..10_000).map(|i| i * 32 + r).collect())
+///     .collect();
+/// let runs_slices: Vec<&[u32]> = runs.iter().map(|r| r.as_slice()).collect();
+/// let mut output = vec![0; 320_000];
+/// adaptive_kway_merge(&runs_slices, &mut output);
+/// assert_eq!(output, (0..320_000).collect::<Vec<u32>>());
+/// ```
+pub fn adaptive_kway_merge<T: Ord + Copy + Send + Sync>(runs: &[&[T]], output: &mut [T]) {
+    assert_eq!(runs.iter().map(|r| r.len()).sum::<usize>(), output.len());
+    if output.is_empty() {
+        return;
+    }
+    let merge = KwayMerge {
+        runs: runs.iter().cloned().filter(|r| !r.is_empty()).collect(),
+        output,
+    };
+    merge.partial_for_each(|mut merge, limit| {
+        let output = mem::take(&mut merge.output);
+        let (done, remaining) = output.split_at_mut(min(limit, output.len()));
+        sequential_merge(&mut merge.runs, done);
+        merge.runs.retain(|r| !r.is_empty());
+        merge.output = remaining;
+        merge
+    });
+}
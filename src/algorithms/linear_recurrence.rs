@@ -0,0 +1,152 @@
+//! adaptive first order linear recurrences.
+use crate::{prelude::*, BlockedPower};
+use rayon::scope;
+use std::cmp::max;
+use std::iter::repeat;
+use std::ops::{Add, Mul};
+
+/// Part of the recurrence still to compute.
+/// Blocks computed by helpers start from 0 and we remember for each element
+/// the product of all multipliers since the block's start.
+/// Each element is then an affine map of the (unknown) value before the block.
+struct RecurrenceSlice<'a, T: 'a> {
+    a: &'a [T],
+    b: &'a [T],
+    output: &'a mut [T],
+    multipliers: &'a mut [T],
+    index: usize,
+}
+
+impl<'a, T: 'a + Send + Sync> RecurrenceSlice<'a, T> {
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (left_a, right_a) = self.a.split_at(index);
+        let (left_b, right_b) = self.b.split_at(index);
+        let (left_output, right_output) = self.output.split_at_mut(index);
+        let (left_multipliers, right_multipliers) = self.multipliers.split_at_mut(index);
+        (
+            RecurrenceSlice {
+                a: left_a,
+                b: left_b,
+                output: left_output,
+                multipliers: left_multipliers,
+                index: self.index,
+            },
+            RecurrenceSlice {
+                a: right_a,
+                b: right_b,
+                output: right_output,
+                multipliers: right_multipliers,
+                index: 0,
+            },
+        )
+    }
+}
+
+impl<'a, T: 'a + Send + Sync> Divisible for RecurrenceSlice<'a, T> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        self.output.len() - self.index
+    }
+    fn divide(self) -> (Self, Self) {
+        let middle = self.index + self.base_length() / 2;
+        self.split_at(middle)
+    }
+}
+
+impl<'a, T: 'a + Send + Sync> DivisibleIntoBlocks for RecurrenceSlice<'a, T> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let middle = self.index + index;
+        self.split_at(middle)
+    }
+}
+
+/// Compute `x[i] = a[i] * x[i-1] + b[i]` for all `i`, `x0` being the value before the first one.
+///
+/// The main thread computes the recurrence sequentially while others help it
+/// by computing later blocks as affine maps of their (unknown) starting value.
+/// When reaching a helped block the main thread composes its map
+/// and spawns a parallel fix-up of the block's elements, like `fully_adaptive_prefix`.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_linear_recurrence;
+///
+/// // discounted returns computed backwards
+/// let rewards: Vec<f64> = (0..100_000).map(|i| (i % 3) as f64).collect();
+/// let discounts = vec![0.5; 100_000];
+/// let reversed_rewards: Vec<f64> = rewards.iter().rev().cloned().collect();
+/// let returns = adaptive_linear_recurrence(&discounts, &reversed_rewards, 0.0);
+/// let mut expected = 0.0;
+/// for (r, computed) in reversed_rewards.iter().zip(returns.iter()) {
+///     expected = 0.5 * expected + r;
+///     assert!((expected - computed).abs() < 1e-9);
+/// }
+/// ```
+pub fn adaptive_linear_recurrence<T>(a: &[T], b: &[T], x0: T) -> Vec<T>
+where
+    T: Copy + Send + Sync + Add<Output = T> + Mul<Output = T>,
+{
+    assert_eq!(a.len(), b.len());
+    let length = a.len();
+    if length == 0 {
+        return Vec::new();
+    }
+    let mut output = vec![x0; length];
+    let mut multipliers = vec![x0; length];
+    let input = RecurrenceSlice {
+        a,
+        b,
+        output: &mut output,
+        multipliers: &mut multipliers,
+        index: 0,
+    };
+    scope(|s| {
+        input
+            .by_blocks(repeat(max(length / 10, 1)))
+            .work(|mut slice, limit| {
+                let start = slice.index;
+                if start == 0 {
+                    // we do not know the value before us : we start from 0
+                    slice.output[0] = slice.b[0];
+                    slice.multipliers[0] = slice.a[0];
+                }
+                for i in max(start, 1)..(start + limit) {
+                    slice.output[i] = slice.a[i] * slice.output[i - 1] + slice.b[i];
+                    slice.multipliers[i] = slice.a[i] * slice.multipliers[i - 1];
+                }
+                slice.index += limit;
+                slice
+            })
+            .map(|slice| (slice.output, slice.multipliers))
+            .helping_cutting_fold(
+                x0,
+                |x, slice| {
+                    slice
+                        .output
+                        .iter_mut()
+                        .zip(slice.a.iter().zip(slice.b.iter()))
+                        .fold(x, |x, (e, (&a, &b))| {
+                            *e = a * x + b;
+                            *e
+                        })
+                },
+                |x, (output, multipliers)| {
+                    if let (Some(&last_output), Some(&last_multiplier)) =
+                        (output.last(), multipliers.last())
+                    {
+                        s.spawn(move |_| {
+                            output
+                                .into_adapt_iter()
+                                .zip(multipliers.into_adapt_iter())
+                                .for_each(|(e, m)| *e = *m * x + *e)
+                        });
+                        last_multiplier * x + last_output
+                    } else {
+                        x
+                    }
+                },
+            )
+    });
+    output
+}
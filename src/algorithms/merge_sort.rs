@@ -3,12 +3,39 @@ use crate::prelude::*;
 use crate::traits::{BasicPower, BlockedPower};
 use crate::{fuse_slices, EdibleSlice, EdibleSliceMut, Policy};
 use std;
+use std::cmp::{max, Ordering};
 use std::iter::repeat;
+use std::mem;
+use std::ptr;
 
 // main related code
 
+/// Move all elements from `source` into `destination` (which has the same size).
+/// Elements are copied bitwise and old values in `destination` are not dropped.
+/// Afterwards `source` is a logically uninitialized copy : caller is responsible
+/// for never dropping its elements.
+pub(crate) unsafe fn move_slice<T>(source: &[T], destination: &mut [T]) {
+    assert_eq!(source.len(), destination.len());
+    ptr::copy_nonoverlapping(source.as_ptr(), destination.as_mut_ptr(), source.len())
+}
+
+/// Compare two options with given comparison function.
+/// Like for `Option`'s `Ord`, `None` is smaller than anything.
+fn compare_options<T, F>(a: Option<&T>, b: Option<&T>, compare: &F) -> Ordering
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    match (a, b) {
+        (Some(a), Some(b)) => compare(a, b),
+        (a, b) => a.is_some().cmp(&b.is_some()),
+    }
+}
+
 /// find subslice without last value in given sorted slice.
-fn subslice_without_last_value<T: Eq>(slice: &[T]) -> &[T] {
+fn subslice_without_last_value<'a, T, F>(slice: &'a [T], compare: &F) -> &'a [T]
+where
+    F: Fn(&T, &T) -> Ordering,
+{
     match slice.split_last() {
         Some((target, slice)) => {
             let searching_range_start = repeat(())
@@ -18,15 +45,15 @@ fn subslice_without_last_value<T: Eq>(slice: &[T]) -> &[T] {
                 }) // iterate on all powers of 2
                 .take_while(|&i| i < slice.len())
                 .map(|i| slice.len() - i) // go farther and farther from end of slice
-                .find(|&i| unsafe { slice.get_unchecked(i) != target })
+                .find(|&i| unsafe { compare(slice.get_unchecked(i), target) != Ordering::Equal })
                 .unwrap_or(0);
 
             let index = slice[searching_range_start..]
                 .binary_search_by(|x| {
-                    if x.eq(target) {
-                        std::cmp::Ordering::Greater
+                    if compare(x, target) == Ordering::Equal {
+                        Ordering::Greater
                     } else {
-                        std::cmp::Ordering::Less
+                        Ordering::Less
                     }
                 })
                 .unwrap_err();
@@ -37,7 +64,10 @@ fn subslice_without_last_value<T: Eq>(slice: &[T]) -> &[T] {
 }
 
 /// find subslice without first value in given sorted slice.
-fn subslice_without_first_value<T: Eq>(slice: &[T]) -> &[T] {
+fn subslice_without_first_value<'a, T, F>(slice: &'a [T], compare: &F) -> &'a [T]
+where
+    F: Fn(&T, &T) -> Ordering,
+{
     match slice.first() {
         Some(target) => {
             let searching_range_end = repeat(())
@@ -46,15 +76,15 @@ fn subslice_without_first_value<T: Eq>(slice: &[T]) -> &[T] {
                     Some(*acc)
                 }) // iterate on all powers of 2
                 .take_while(|&i| i < slice.len())
-                .find(|&i| unsafe { slice.get_unchecked(i) != target })
+                .find(|&i| unsafe { compare(slice.get_unchecked(i), target) != Ordering::Equal })
                 .unwrap_or_else(|| slice.len());
 
             let index = slice[..searching_range_end]
                 .binary_search_by(|x| {
-                    if x.eq(target) {
-                        std::cmp::Ordering::Less
+                    if compare(x, target) == Ordering::Equal {
+                        Ordering::Less
                     } else {
-                        std::cmp::Ordering::Greater
+                        Ordering::Greater
                     }
                 })
                 .unwrap_err();
@@ -67,22 +97,33 @@ fn subslice_without_first_value<T: Eq>(slice: &[T]) -> &[T] {
 /// Cut sorted slice `slice` around start point, splitting around
 /// all values equal to value at start point.
 /// cost is O(log(|removed part size|))
-fn split_around<T: Eq>(slice: &[T], start: usize) -> (&[T], &[T], &[T]) {
-    let low_slice = subslice_without_last_value(&slice[0..=start]);
-    let high_slice = subslice_without_first_value(&slice[start..]);
+pub(crate) fn split_around<'a, T, F>(
+    slice: &'a [T],
+    start: usize,
+    compare: &F,
+) -> (&'a [T], &'a [T], &'a [T])
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let low_slice = subslice_without_last_value(&slice[0..=start], compare);
+    let high_slice = subslice_without_first_value(&slice[start..], compare);
     let equal_slice = &slice[low_slice.len()..slice.len() - high_slice.len()];
     (low_slice, equal_slice, high_slice)
 }
 
 /// split large array at midpoint and small array where needed for merge.
-fn merge_split<'a, T: Ord>(
+pub(crate) fn merge_split<'a, T, F>(
     large: &'a [T],
     small: &'a [T],
-) -> ((&'a [T], &'a [T], &'a [T]), (&'a [T], &'a [T], &'a [T])) {
+    compare: &F,
+) -> ((&'a [T], &'a [T], &'a [T]), (&'a [T], &'a [T], &'a [T]))
+where
+    F: Fn(&T, &T) -> Ordering,
+{
     let middle = large.len() / 2;
-    let split_large = split_around(large, middle);
-    let split_small = match small.binary_search(&large[middle]) {
-        Ok(i) => split_around(small, i),
+    let split_large = split_around(large, middle, compare);
+    let split_small = match small.binary_search_by(|x| compare(x, &large[middle])) {
+        Ok(i) => split_around(small, i, compare),
         Err(i) => {
             let (small1, small3) = small.split_at(i);
             (small1, &small[0..0], small3)
@@ -92,13 +133,18 @@ fn merge_split<'a, T: Ord>(
 }
 
 #[derive(Debug)]
-struct FusionSlice<'a, T: 'a> {
+struct FusionSlice<'a, T: 'a, F: 'a> {
     left: EdibleSlice<'a, T>,
     right: EdibleSlice<'a, T>,
     output: EdibleSliceMut<'a, T>,
+    compare: &'a F,
 }
 
-impl<'a, T: 'a + Send + Sync + Ord + Copy> Divisible for FusionSlice<'a, T> {
+impl<'a, T, F> Divisible for FusionSlice<'a, T, F>
+where
+    T: 'a + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
     type Power = BasicPower;
     fn base_length(&self) -> usize {
         self.output.base_length()
@@ -108,37 +154,49 @@ impl<'a, T: 'a + Send + Sync + Ord + Copy> Divisible for FusionSlice<'a, T> {
         let right = self.right.remaining_slice();
         let output = self.output.into_remaining_slice();
         let ((l1, l2, l3), (r1, r2, r3)) = if left.len() > right.len() {
-            merge_split(left, right)
+            merge_split(left, right, self.compare)
         } else {
-            let (r, l) = merge_split(right, left);
+            let (r, l) = merge_split(right, left, self.compare);
             (l, r)
         };
         let (o1, ol) = output.split_at_mut(l1.len() + r1.len());
         let (o2, o3) = ol.split_at_mut(l2.len() + r2.len());
-        // immediately copy sequentially the middle part
-        o2[..l2.len()].copy_from_slice(l2);
-        o2[l2.len()..].copy_from_slice(r2);
+        // immediately move sequentially the middle part
+        unsafe {
+            move_slice(l2, &mut o2[..l2.len()]);
+            move_slice(r2, &mut o2[l2.len()..]);
+        }
         // return what is left to do
         (
             FusionSlice {
                 left: EdibleSlice::new(l1),
                 right: EdibleSlice::new(r1),
                 output: EdibleSliceMut::new(o1),
+                compare: self.compare,
             },
             FusionSlice {
                 left: EdibleSlice::new(l3),
                 right: EdibleSlice::new(r3),
                 output: EdibleSliceMut::new(o3),
+                compare: self.compare,
             },
         )
     }
 }
 
-fn fuse<T: Ord + Send + Sync + Copy>(left: &[T], right: &[T], output: &mut [T], policy: Policy) {
+/// Merge `left` and `right` into `output`.
+/// Elements are moved : inputs are left as logically uninitialized copies.
+/// If `compare` panics, inputs are untouched and still hold all elements.
+fn fuse<T, F>(left: &[T], right: &[T], output: &mut [T], policy: Policy, compare: &F)
+where
+    T: Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
     let slices = FusionSlice {
         left: EdibleSlice::new(left),
         right: EdibleSlice::new(right),
         output: EdibleSliceMut::new(output),
+        compare,
     };
 
     slices
@@ -149,39 +207,45 @@ fn fuse<T: Ord + Send + Sync + Copy>(left: &[T], right: &[T], output: &mut [T],
                     let mut left_i = slices.left.iter();
                     let mut right_i = slices.right.iter();
                     for o in slices.output.iter_mut().take(limit) {
-                        let go_left = left_i.peek() <= right_i.peek();
-                        *o = if go_left {
-                            *left_i.next().unwrap()
+                        let go_left = compare_options(left_i.peek(), right_i.peek(), compare)
+                            != Ordering::Greater;
+                        let source = if go_left {
+                            left_i.next().unwrap()
                         } else {
-                            *right_i.next().unwrap()
+                            right_i.next().unwrap()
                         };
+                        unsafe { ptr::copy_nonoverlapping(source, o, 1) };
                     }
                 } else {
                     let mut left_i = slices.left.iter();
                     let mut right_i = slices.right.iter();
                     for o in slices.output.iter_mut().take(limit) {
-                        let go_left = left_i.peek() <= right_i.peek();
+                        let go_left = compare_options(left_i.peek(), right_i.peek(), compare)
+                            != Ordering::Greater;
                         if go_left {
                             if left_i.peek().is_none() {
-                                *o = *right_i.next().unwrap();
-                                slices
-                                    .output
-                                    .eat_remaining_slice()
-                                    .copy_from_slice(slices.right.eat_remaining_slice());
-
+                                unsafe {
+                                    ptr::copy_nonoverlapping(right_i.next().unwrap(), o, 1);
+                                    move_slice(
+                                        slices.right.eat_remaining_slice(),
+                                        slices.output.eat_remaining_slice(),
+                                    );
+                                }
                                 break;
                             }
-                            *o = *left_i.next().unwrap();
+                            unsafe { ptr::copy_nonoverlapping(left_i.next().unwrap(), o, 1) };
                         } else {
                             if right_i.peek().is_none() {
-                                *o = *left_i.next().unwrap();
-                                slices
-                                    .output
-                                    .eat_remaining_slice()
-                                    .copy_from_slice(slices.left.eat_remaining_slice());
+                                unsafe {
+                                    ptr::copy_nonoverlapping(left_i.next().unwrap(), o, 1);
+                                    move_slice(
+                                        slices.left.eat_remaining_slice(),
+                                        slices.output.eat_remaining_slice(),
+                                    );
+                                }
                                 break;
                             }
-                            *o = *right_i.next().unwrap();
+                            unsafe { ptr::copy_nonoverlapping(right_i.next().unwrap(), o, 1) };
                         };
                     }
                 }
@@ -190,24 +254,107 @@ fn fuse<T: Ord + Send + Sync + Copy>(left: &[T], right: &[T], output: &mut [T],
         });
 }
 
+/// Merge sorted slices `left` and `right` into `output` using given scheduling policy.
+/// `output` must be exactly as large as both inputs together.
+/// Merge is stable : on equal elements, elements of `left` come first.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::{adaptive_merge, Policy};
+///
+/// let left: Vec<u32> = (0..100_000).map(|i| 2 * i).collect();
+/// let right: Vec<u32> = (0..100_000).map(|i| 2 * i + 1).collect();
+/// let mut output = vec![0; 200_000];
+/// adaptive_merge(&left, &right, &mut output, Policy::DefaultPolicy);
+/// assert_eq!(output, (0..200_000).collect::<Vec<u32>>());
+/// ```
+pub fn adaptive_merge<T: Ord + Copy + Send + Sync>(
+    left: &[T],
+    right: &[T],
+    output: &mut [T],
+    policy: Policy,
+) {
+    adaptive_merge_by(left, right, output, policy, T::cmp)
+}
+
+/// Merge slices `left` and `right` (sorted according to `compare`) into `output`
+/// using given scheduling policy.
+/// `output` must be exactly as large as both inputs together.
+/// Merge is stable : on equal elements, elements of `left` come first.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::{adaptive_merge_by, Policy};
+///
+/// let left: Vec<(u32, char)> = (0..50_000).rev().map(|i| (i, 'l')).collect();
+/// let right: Vec<(u32, char)> = (0..50_000).rev().map(|i| (i, 'r')).collect();
+/// let mut output = vec![(0, ' '); 100_000];
+/// adaptive_merge_by(&left, &right, &mut output, Policy::Adaptive(1000, 50_000), |a, b| {
+///     b.0.cmp(&a.0)
+/// });
+/// assert!(output
+///     .chunks(2)
+///     .zip((0..50_000).rev())
+///     .all(|(c, i)| c == [(i, 'l'), (i, 'r')]));
+/// ```
+pub fn adaptive_merge_by<T, F>(
+    left: &[T],
+    right: &[T],
+    output: &mut [T],
+    policy: Policy,
+    compare: F,
+) where
+    T: Copy + Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    assert_eq!(left.len() + right.len(), output.len());
+    if left.is_empty() || right.is_empty() {
+        output[..left.len()].copy_from_slice(left);
+        output[left.len()..].copy_from_slice(right);
+    } else {
+        // elements are copy : moving them out leaves inputs valid
+        fuse(left, right, output, policy, &compare)
+    }
+}
+
 // sort related code
 
 /// We'll need slices of several vectors at once.
+/// Elements are owned by the input slice (index 0) but live in slice of index `i`.
 #[derive(Debug)]
 struct SortingSlices<'a, T: 'a> {
     s: Vec<&'a mut [T]>,
     i: usize,
 }
 
-impl<'a, T: 'a + Ord + Sync + Copy + Send> SortingSlices<'a, T> {
+impl<'a, T: 'a> Drop for SortingSlices<'a, T> {
+    /// Move back data into the input slice if needed.
+    /// This ends the sort but also ensures no element is lost or duplicated
+    /// when unwinding from a panicking comparison function.
+    fn drop(&mut self) {
+        if !self.s.is_empty() && self.i != 0 {
+            let i = self.i;
+            let (destination, source) = self.mut_couple(0, i);
+            unsafe { move_slice(source, destination) }
+        }
+    }
+}
+
+impl<'a, T: 'a + Sync + Send> SortingSlices<'a, T> {
     /// Call parallel merge on the right slices.
-    fn fuse_with_policy(self, other: Self, policy: Policy) -> Self {
+    fn fuse_with_policy<F>(self, other: Self, policy: Policy, compare: &F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Sync,
+    {
         let mut left = self;
         let mut right = other;
         // let's try a nice optimization here for nearly sorted arrays.
         // if slices are already sorted and at same index then we do nothing !
         let destination_index = if left.i == right.i
-            && left.s[left.i].last() <= right.s[right.i].first()
+            && compare_options(left.s[left.i].last(), right.s[right.i].first(), compare)
+                != Ordering::Greater
         {
             left.i
         } else {
@@ -219,22 +366,32 @@ impl<'a, T: 'a + Ord + Sync + Copy + Send> SortingSlices<'a, T> {
                 let (right_input, right_output) = right.mut_couple(right_index, destination_index);
                 let output_slice = fuse_slices(left_output, right_output);
                 // if slices are nearly sorted we will resort to memcpy
-                if left_input.last() <= right_input.first() {
-                    output_slice[..left_input.base_length()].copy_from_slice(left_input);
-                    output_slice[left_input.base_length()..].copy_from_slice(right_input);
-                } else if right_input.last() < left_input.first() {
-                    output_slice[..right_input.base_length()].copy_from_slice(right_input);
-                    output_slice[right_input.base_length()..].copy_from_slice(left_input);
+                if compare_options(left_input.last(), right_input.first(), compare)
+                    != Ordering::Greater
+                {
+                    unsafe {
+                        move_slice(left_input, &mut output_slice[..left_input.base_length()]);
+                        move_slice(right_input, &mut output_slice[left_input.base_length()..]);
+                    }
+                } else if compare_options(right_input.last(), left_input.first(), compare)
+                    == Ordering::Less
+                {
+                    unsafe {
+                        move_slice(right_input, &mut output_slice[..right_input.base_length()]);
+                        move_slice(left_input, &mut output_slice[right_input.base_length()..]);
+                    }
                 } else {
-                    fuse(left_input, right_input, output_slice, policy);
+                    fuse(left_input, right_input, output_slice, policy, compare);
                 }
             }
             destination_index
         };
-        let fused_slices: Vec<_> = left
-            .s
+        // data is now in destination, we take the slices out of the guards
+        let left_slices = mem::take(&mut left.s);
+        let right_slices = mem::take(&mut right.s);
+        let fused_slices: Vec<_> = left_slices
             .into_iter()
-            .zip(right.s.into_iter())
+            .zip(right_slices)
             .map(|(left_s, right_s)| fuse_slices(left_s, right_s))
             .collect();
         SortingSlices {
@@ -243,6 +400,23 @@ impl<'a, T: 'a + Ord + Sync + Copy + Send> SortingSlices<'a, T> {
         }
     }
 
+    fn split_at(mut self, i: usize) -> (Self, Self) {
+        let v = mem::take(&mut self.s)
+            .into_iter()
+            .map(|s| s.split_at_mut(i))
+            .fold((Vec::new(), Vec::new()), |mut acc, (s1, s2)| {
+                acc.0.push(s1);
+                acc.1.push(s2);
+                acc
+            });
+        (
+            SortingSlices { s: v.0, i: self.i },
+            SortingSlices { s: v.1, i: self.i },
+        )
+    }
+}
+
+impl<'a, T: 'a> SortingSlices<'a, T> {
     /// Borrow all mutable slices at once.
     fn mut_slices(&mut self) -> (&mut [T], &mut [T], &mut [T]) {
         let (s0, leftover) = self.s.split_first_mut().unwrap();
@@ -262,23 +436,9 @@ impl<'a, T: 'a + Ord + Sync + Copy + Send> SortingSlices<'a, T> {
             _ => panic!("i1 == i2"),
         }
     }
-    fn split_at(self, i: usize) -> (Self, Self) {
-        let v = self.s.into_iter().map(|s| s.split_at_mut(i)).fold(
-            (Vec::new(), Vec::new()),
-            |mut acc, (s1, s2)| {
-                acc.0.push(s1);
-                acc.1.push(s2);
-                acc
-            },
-        );
-        (
-            SortingSlices { s: v.0, i: self.i },
-            SortingSlices { s: v.1, i: self.i },
-        )
-    }
 }
 
-impl<'a, T: 'a + Ord + Copy + Sync + Send> Divisible for SortingSlices<'a, T> {
+impl<'a, T: 'a + Sync + Send> Divisible for SortingSlices<'a, T> {
     type Power = BlockedPower;
     fn base_length(&self) -> usize {
         self.s[0].base_length()
@@ -289,14 +449,14 @@ impl<'a, T: 'a + Ord + Copy + Sync + Send> Divisible for SortingSlices<'a, T> {
     }
 }
 
-impl<'a, T: 'a + Ord + Copy + Sync + Send> DivisibleIntoBlocks for SortingSlices<'a, T> {
+impl<'a, T: 'a + Sync + Send> DivisibleIntoBlocks for SortingSlices<'a, T> {
     fn divide_at(self, i: usize) -> (Self, Self) {
         self.split_at(i)
     }
 }
 
 /// Sort given slice using an adaptive version of merge sort.
-/// For now we require Copy on T.
+/// Elements are moved around (no Copy required) and sort is panic safe.
 /// Sort is stable.
 ///
 /// # Examples
@@ -314,35 +474,99 @@ impl<'a, T: 'a + Ord + Copy + Sync + Send> DivisibleIntoBlocks for SortingSlices
 /// adaptive_sort(&mut inverted_v);
 /// assert_eq!(v, inverted_v);
 /// assert_eq!(v, random_v);
+///
+/// let mut strings: Vec<String> = (0..10_000).rev().map(|i| format!("{:05}", i)).collect();
+/// adaptive_sort(&mut strings);
+/// assert!(strings.windows(2).all(|w| w[0] <= w[1]));
+///
+/// let mut empty: Vec<u32> = Vec::new();
+/// adaptive_sort(&mut empty);
+/// assert!(empty.is_empty());
 /// ```
-pub fn adaptive_sort<T: Ord + Copy + Send + Sync>(slice: &mut [T]) {
-    let mut tmp_slice1 = Vec::with_capacity(slice.base_length());
-    let mut tmp_slice2 = Vec::with_capacity(slice.base_length());
-    unsafe {
-        tmp_slice1.set_len(slice.base_length());
-        tmp_slice2.set_len(slice.base_length());
-    }
+pub fn adaptive_sort<T: Ord + Send + Sync>(slice: &mut [T]) {
+    adaptive_sort_by(slice, T::cmp)
+}
+
+/// Sort given slice with a comparator function using an adaptive version of merge sort.
+/// If `compare` panics, slice is left in an unspecified order but no element is lost.
+/// Sort is stable.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_sort_by;
+/// use rand::{thread_rng, Rng};
+///
+/// let v: Vec<u32> = (0..100_000).rev().collect();
+/// let mut rng = thread_rng();
+/// let mut random_v: Vec<u32> = (0..100_000).collect();
+/// rng.shuffle(&mut random_v);
+/// adaptive_sort_by(&mut random_v, |a, b| b.cmp(a));
+/// assert_eq!(v, random_v);
+///
+/// let mut empty: Vec<String> = Vec::new();
+/// adaptive_sort_by(&mut empty, |a, b| a.cmp(b));
+/// assert!(empty.is_empty());
+/// ```
+pub fn adaptive_sort_by<T, F>(slice: &mut [T], compare: F)
+where
+    T: Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
     let slice_len = slice.len();
+    if slice_len <= 1 {
+        return;
+    }
+    // temporary vectors stay empty : they only provide memory.
+    // elements are always owned by the input slice.
+    let mut tmp_vec1: Vec<T> = Vec::with_capacity(slice_len);
+    let mut tmp_vec2: Vec<T> = Vec::with_capacity(slice_len);
+    let (tmp_slice1, tmp_slice2) = unsafe {
+        (
+            std::slice::from_raw_parts_mut(tmp_vec1.as_mut_ptr(), slice_len),
+            std::slice::from_raw_parts_mut(tmp_vec2.as_mut_ptr(), slice_len),
+        )
+    };
     let num_threads = rayon::current_num_threads();
 
     let slices = SortingSlices {
-        s: vec![slice, tmp_slice1.as_mut_slice(), tmp_slice2.as_mut_slice()],
+        s: vec![slice, tmp_slice1, tmp_slice2],
         i: 0,
     };
 
-    let mut result_slices = slices
-        .with_policy(Policy::DepJoin(slice_len / (2 * num_threads)))
+    let compare_ref = &compare;
+    let result_slices = slices
+        .with_policy(Policy::DepJoin(max(slice_len / (2 * num_threads), 1)))
         .map_reduce(
             |mut slices| {
-                slices.s[slices.i].sort();
+                slices.s[slices.i].sort_by(compare_ref);
                 slices
             },
-            |s1, s2| s1.fuse_with_policy(s2, Default::default()),
+            |s1, s2| s1.fuse_with_policy(s2, Default::default(), compare_ref),
         );
+    // dropping moves back the sorted data into the input slice if needed
+    mem::drop(result_slices);
+}
 
-    if result_slices.i != 0 {
-        let i = result_slices.i;
-        let (destination, source) = result_slices.mut_couple(0, i);
-        destination.copy_from_slice(source);
-    }
+/// Sort given slice with a key extraction function using an adaptive version of merge sort.
+/// Sort is stable.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_sort_by_key;
+///
+/// let mut v: Vec<(u32, u32)> = (0..100_000).map(|i| (i % 7, i)).collect();
+/// adaptive_sort_by_key(&mut v, |&(k, _)| k);
+/// let mut sorted_v: Vec<(u32, u32)> = (0..100_000).map(|i| (i % 7, i)).collect();
+/// sorted_v.sort_by_key(|&(k, _)| k);
+/// assert_eq!(v, sorted_v);
+/// ```
+pub fn adaptive_sort_by_key<T, K, F>(slice: &mut [T], key: F)
+where
+    T: Send + Sync,
+    K: Ord,
+    F: Fn(&T) -> K + Sync,
+{
+    adaptive_sort_by(slice, |a, b| key(a).cmp(&key(b)))
 }
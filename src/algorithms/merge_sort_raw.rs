@@ -1,9 +1,13 @@
 //! adaptive parallel merge sort.
+use super::merge_sort::move_slice;
 use crate::prelude::*;
 use crate::traits::{BasicPower, BlockedPower};
 use crate::{fuse_slices, Policy};
 use std;
+use std::cmp::max;
 use std::iter::repeat;
+use std::mem;
+use std::ptr;
 
 // main related code
 
@@ -101,7 +105,7 @@ struct FusionSlice<'a, T: 'a> {
     output_index: usize,
 }
 
-impl<'a, T: 'a + Send + Sync + Ord + Copy> Divisible for FusionSlice<'a, T> {
+impl<'a, T: 'a + Send + Sync + Ord> Divisible for FusionSlice<'a, T> {
     type Power = BasicPower;
     fn base_length(&self) -> usize {
         self.output.len() - self.output_index
@@ -118,9 +122,11 @@ impl<'a, T: 'a + Send + Sync + Ord + Copy> Divisible for FusionSlice<'a, T> {
         };
         let (o1, ol) = output.split_at_mut(l1.len() + r1.len());
         let (o2, o3) = ol.split_at_mut(l2.len() + r2.len());
-        // immediately copy sequentially the middle part
-        o2[..l2.len()].copy_from_slice(l2);
-        o2[l2.len()..].copy_from_slice(r2);
+        // immediately move sequentially the middle part
+        unsafe {
+            move_slice(l2, &mut o2[..l2.len()]);
+            move_slice(r2, &mut o2[l2.len()..]);
+        }
         // return what is left to do
         (
             FusionSlice {
@@ -143,7 +149,10 @@ impl<'a, T: 'a + Send + Sync + Ord + Copy> Divisible for FusionSlice<'a, T> {
     }
 }
 
-fn fuse<T: Ord + Send + Sync + Copy>(left: &[T], right: &[T], output: &mut [T], policy: Policy) {
+/// Merge `left` and `right` into `output`.
+/// Elements are moved : inputs are left as logically uninitialized copies.
+/// If comparisons panic, inputs are untouched and still hold all elements.
+fn fuse<T: Ord + Send + Sync>(left: &[T], right: &[T], output: &mut [T], policy: Policy) {
     let slices = FusionSlice {
         left: left,
         left_index: 0,
@@ -164,33 +173,42 @@ fn fuse<T: Ord + Send + Sync + Copy>(left: &[T], right: &[T], output: &mut [T],
                         let output_ref =
                             unsafe { slices.output.get_unchecked_mut(slices.output_index) };
                         slices.output_index += 1;
-                        *output_ref = unsafe {
-                            if slices.left.get_unchecked(slices.left_index)
+                        unsafe {
+                            let temp = if slices.left.get_unchecked(slices.left_index)
                                 <= slices.right.get_unchecked(slices.right_index)
                             {
                                 let temp = slices.left.get_unchecked(slices.left_index);
                                 slices.left_index += 1;
-                                *temp
+                                temp
                             } else {
                                 let temp = slices.right.get_unchecked(slices.right_index);
                                 slices.right_index += 1;
-                                *temp
-                            }
+                                temp
+                            };
+                            ptr::copy_nonoverlapping(temp, output_ref, 1);
                         }
                     }
                 } else {
                     for _ in 0..limit {
                         if slices.right.len() <= slices.right_index {
                             //Go left all the way.
-                            slices.output[slices.output_index..]
-                                .copy_from_slice(&slices.left[slices.left_index..]);
+                            unsafe {
+                                move_slice(
+                                    &slices.left[slices.left_index..],
+                                    &mut slices.output[slices.output_index..],
+                                );
+                            }
                             slices.left_index = slices.left.len();
                             slices.output_index = slices.output.len();
                             break;
                         }
                         if slices.left.len() <= slices.left_index {
-                            slices.output[slices.output_index..]
-                                .copy_from_slice(&slices.right[slices.right_index..]);
+                            unsafe {
+                                move_slice(
+                                    &slices.right[slices.right_index..],
+                                    &mut slices.output[slices.output_index..],
+                                );
+                            }
                             slices.right_index = slices.right.len();
                             slices.output_index = slices.output.len();
                             break;
@@ -198,18 +216,19 @@ fn fuse<T: Ord + Send + Sync + Copy>(left: &[T], right: &[T], output: &mut [T],
                         let output_ref =
                             unsafe { slices.output.get_unchecked_mut(slices.output_index) };
                         slices.output_index += 1;
-                        *output_ref = unsafe {
-                            if slices.left.get_unchecked(slices.left_index)
+                        unsafe {
+                            let temp = if slices.left.get_unchecked(slices.left_index)
                                 <= slices.right.get_unchecked(slices.right_index)
                             {
                                 let temp = slices.left.get_unchecked(slices.left_index);
                                 slices.left_index += 1;
-                                *temp
+                                temp
                             } else {
                                 let temp = slices.right.get_unchecked(slices.right_index);
                                 slices.right_index += 1;
-                                *temp
-                            }
+                                temp
+                            };
+                            ptr::copy_nonoverlapping(temp, output_ref, 1);
                         }
                     }
                 }
@@ -221,13 +240,27 @@ fn fuse<T: Ord + Send + Sync + Copy>(left: &[T], right: &[T], output: &mut [T],
 // sort related code
 
 /// We'll need slices of several vectors at once.
+/// Elements are owned by the input slice (index 0) but live in slice of index `i`.
 #[derive(Debug)]
 struct SortingSlices<'a, T: 'a> {
     s: Vec<&'a mut [T]>,
     i: usize,
 }
 
-impl<'a, T: 'a + Ord + Sync + Copy + Send> SortingSlices<'a, T> {
+impl<'a, T: 'a> Drop for SortingSlices<'a, T> {
+    /// Move back data into the input slice if needed.
+    /// This ends the sort but also ensures no element is lost or duplicated
+    /// when unwinding from a panicking comparison.
+    fn drop(&mut self) {
+        if !self.s.is_empty() && self.i != 0 {
+            let i = self.i;
+            let (destination, source) = self.mut_couple(0, i);
+            unsafe { move_slice(source, destination) }
+        }
+    }
+}
+
+impl<'a, T: 'a + Ord + Sync + Send> SortingSlices<'a, T> {
     /// Call parallel merge on the right slices.
     fn fuse_with_policy(self, other: Self, policy: Policy) -> Self {
         let mut left = self;
@@ -248,21 +281,27 @@ impl<'a, T: 'a + Ord + Sync + Copy + Send> SortingSlices<'a, T> {
                 let output_slice = fuse_slices(left_output, right_output);
                 // if slices are nearly sorted we will resort to memcpy
                 if left_input.last() <= right_input.first() {
-                    output_slice[..left_input.base_length()].copy_from_slice(left_input);
-                    output_slice[left_input.base_length()..].copy_from_slice(right_input);
+                    unsafe {
+                        move_slice(left_input, &mut output_slice[..left_input.base_length()]);
+                        move_slice(right_input, &mut output_slice[left_input.base_length()..]);
+                    }
                 } else if right_input.last() < left_input.first() {
-                    output_slice[..right_input.base_length()].copy_from_slice(right_input);
-                    output_slice[right_input.base_length()..].copy_from_slice(left_input);
+                    unsafe {
+                        move_slice(right_input, &mut output_slice[..right_input.base_length()]);
+                        move_slice(left_input, &mut output_slice[right_input.base_length()..]);
+                    }
                 } else {
                     fuse(left_input, right_input, output_slice, policy);
                 }
             }
             destination_index
         };
-        let fused_slices: Vec<_> = left
-            .s
+        // data is now in destination, we take the slices out of the guards
+        let left_slices = mem::take(&mut left.s);
+        let right_slices = mem::take(&mut right.s);
+        let fused_slices: Vec<_> = left_slices
             .into_iter()
-            .zip(right.s.into_iter())
+            .zip(right_slices)
             .map(|(left_s, right_s)| fuse_slices(left_s, right_s))
             .collect();
         SortingSlices {
@@ -271,6 +310,23 @@ impl<'a, T: 'a + Ord + Sync + Copy + Send> SortingSlices<'a, T> {
         }
     }
 
+    fn split_at(mut self, i: usize) -> (Self, Self) {
+        let v = mem::take(&mut self.s)
+            .into_iter()
+            .map(|s| s.split_at_mut(i))
+            .fold((Vec::new(), Vec::new()), |mut acc, (s1, s2)| {
+                acc.0.push(s1);
+                acc.1.push(s2);
+                acc
+            });
+        (
+            SortingSlices { s: v.0, i: self.i },
+            SortingSlices { s: v.1, i: self.i },
+        )
+    }
+}
+
+impl<'a, T: 'a> SortingSlices<'a, T> {
     /// Borrow all mutable slices at once.
     fn mut_slices(&mut self) -> (&mut [T], &mut [T], &mut [T]) {
         let (s0, leftover) = self.s.split_first_mut().unwrap();
@@ -290,23 +346,9 @@ impl<'a, T: 'a + Ord + Sync + Copy + Send> SortingSlices<'a, T> {
             _ => panic!("i1 == i2"),
         }
     }
-    fn split_at(self, i: usize) -> (Self, Self) {
-        let v = self.s.into_iter().map(|s| s.split_at_mut(i)).fold(
-            (Vec::new(), Vec::new()),
-            |mut acc, (s1, s2)| {
-                acc.0.push(s1);
-                acc.1.push(s2);
-                acc
-            },
-        );
-        (
-            SortingSlices { s: v.0, i: self.i },
-            SortingSlices { s: v.1, i: self.i },
-        )
-    }
 }
 
-impl<'a, T: 'a + Ord + Copy + Sync + Send> Divisible for SortingSlices<'a, T> {
+impl<'a, T: 'a + Ord + Sync + Send> Divisible for SortingSlices<'a, T> {
     type Power = BlockedPower;
     fn base_length(&self) -> usize {
         self.s[0].base_length()
@@ -317,14 +359,14 @@ impl<'a, T: 'a + Ord + Copy + Sync + Send> Divisible for SortingSlices<'a, T> {
     }
 }
 
-impl<'a, T: 'a + Ord + Copy + Sync + Send> DivisibleIntoBlocks for SortingSlices<'a, T> {
+impl<'a, T: 'a + Ord + Sync + Send> DivisibleIntoBlocks for SortingSlices<'a, T> {
     fn divide_at(self, i: usize) -> (Self, Self) {
         self.split_at(i)
     }
 }
 
 /// Sort given slice using an adaptive version of merge sort.
-/// For now we require Copy on T.
+/// Elements are moved around (no Copy required) and sort is panic safe.
 /// Sort is stable.
 ///
 /// # Examples
@@ -342,25 +384,36 @@ impl<'a, T: 'a + Ord + Copy + Sync + Send> DivisibleIntoBlocks for SortingSlices
 /// adaptive_sort_raw(&mut inverted_v);
 /// assert_eq!(v, inverted_v);
 /// assert_eq!(v, random_v);
+///
+/// let mut empty: Vec<u32> = Vec::new();
+/// adaptive_sort_raw(&mut empty);
+/// assert!(empty.is_empty());
 /// ```
-pub fn adaptive_sort_raw<T: Ord + Copy + Send + Sync>(slice: &mut [T]) {
-    let mut tmp_slice1 = Vec::with_capacity(slice.base_length());
-    let mut tmp_slice2 = Vec::with_capacity(slice.base_length());
-    unsafe {
-        tmp_slice1.set_len(slice.base_length());
-        tmp_slice2.set_len(slice.base_length());
+pub fn adaptive_sort_raw<T: Ord + Send + Sync>(slice: &mut [T]) {
+    let slice_len = slice.len();
+    if slice_len <= 1 {
+        return;
     }
+    // temporary vectors stay empty : they only provide memory.
+    // elements are always owned by the input slice.
+    let mut tmp_vec1: Vec<T> = Vec::with_capacity(slice_len);
+    let mut tmp_vec2: Vec<T> = Vec::with_capacity(slice_len);
+    let (tmp_slice1, tmp_slice2) = unsafe {
+        (
+            std::slice::from_raw_parts_mut(tmp_vec1.as_mut_ptr(), slice_len),
+            std::slice::from_raw_parts_mut(tmp_vec2.as_mut_ptr(), slice_len),
+        )
+    };
 
-    let slice_len = slice.len();
     let num_threads = rayon::current_num_threads();
 
     let slices = SortingSlices {
-        s: vec![slice, tmp_slice1.as_mut_slice(), tmp_slice2.as_mut_slice()],
+        s: vec![slice, tmp_slice1, tmp_slice2],
         i: 0,
     };
 
-    let mut result_slices = slices
-        .with_policy(Policy::DepJoin(slice_len / (num_threads * 2)))
+    let result_slices = slices
+        .with_policy(Policy::DepJoin(max(slice_len / (num_threads * 2), 1)))
         .map_reduce(
             |mut slices| {
                 slices.s[slices.i].sort();
@@ -368,10 +421,6 @@ pub fn adaptive_sort_raw<T: Ord + Copy + Send + Sync>(slice: &mut [T]) {
             },
             |s1, s2| s1.fuse_with_policy(s2, Default::default()),
         );
-
-    if result_slices.i != 0 {
-        let i = result_slices.i;
-        let (destination, source) = result_slices.mut_couple(0, i);
-        destination.copy_from_slice(source);
-    }
+    // dropping moves back the sorted data into the input slice if needed
+    mem::drop(result_slices);
 }
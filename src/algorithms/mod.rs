@@ -1,6 +1,23 @@
 //! adaptive algorithms
 
+pub(crate) mod batch_search;
+pub(crate) mod expression;
+pub(crate) mod fsm;
+pub(crate) mod group_by;
+pub(crate) mod histogram;
 pub(crate) mod infix_solvers;
+pub(crate) mod join;
+pub(crate) mod kway_merge;
+pub(crate) mod linear_recurrence;
 pub(crate) mod merge_sort;
 pub(crate) mod merge_sort_raw;
 pub(crate) mod prefix;
+pub(crate) mod quick_sort;
+pub(crate) mod radix_sort;
+pub(crate) mod retain;
+pub(crate) mod scans;
+pub(crate) mod selection;
+pub(crate) mod sorted_sets;
+pub(crate) mod substring;
+pub(crate) mod utf8;
+pub(crate) mod wc;
@@ -2,6 +2,7 @@
 //! No macro blocks.
 use crate::{prelude::*, BlockedPower, EdibleSliceMut};
 use rayon::scope;
+use std::cmp::min;
 use std::iter::repeat;
 
 /// Run adaptive prefix algortihm on given slice.
@@ -21,44 +22,105 @@ pub fn adaptive_prefix<T, O>(v: &mut [T], op: O)
 where
     T: Send + Sync + Clone,
     O: Fn(&T, &T) -> T + Sync,
+{
+    prefix_in_place(v, |e, previous| *e = op(e, previous))
+}
+
+/// Adaptive prefix where `update(e, previous)` folds in place the previous (already updated)
+/// element into `e`.
+/// Blocks are processed sequentially, pre-updating the first element of the next block.
+/// Remaining blocks are then updated in parallel with the last element of the block before them.
+fn prefix_in_place<E, U>(v: &mut [E], update: U)
+where
+    E: Send + Sync,
+    U: Fn(&mut E, &E) + Sync,
 {
     let input = EdibleSliceMut::new(v);
     input
         .work(|mut slice, limit| {
-            let c = {
-                let mut elements = slice.iter_mut().take(limit);
-                let mut c = elements.next().unwrap().clone();
-                for e in elements {
-                    *e = op(e, &c);
-                    c = e.clone();
+            {
+                let remaining = slice.remaining_slice();
+                // we go one element further to pre-update next one
+                let end = min(limit + 1, remaining.len());
+                for i in 1..end {
+                    let (previous, current) = remaining.split_at_mut(i);
+                    update(&mut current[0], &previous[i - 1]);
                 }
-                c
-            };
-            // pre-update next one
-            if let Some(e) = slice.peek() {
-                *e = op(e, &c);
             }
+            slice.iter_mut().nth(limit - 1); // mark the block as used
             slice
         })
         .map(|slice| slice.slice())
         .into_iter()
         .fold(
             None,
-            |potential_previous_slice: Option<&mut [T]>, current_slice| {
+            |potential_previous_slice: Option<&mut [E]>, current_slice| {
                 if let Some(previous_slice) = potential_previous_slice {
-                    update(current_slice, previous_slice.last().cloned().unwrap(), &op);
+                    let increment = previous_slice.last().unwrap();
+                    current_slice
+                        .into_adapt_iter()
+                        .for_each(|e| update(e, increment));
                 }
                 Some(current_slice)
             },
         );
 }
 
-fn update<T, O>(slice: &mut [T], increment: T, op: &O)
+// now the 2D version
+
+/// Run adaptive 2D prefix (summed-area table) on given row-major matrix.
+/// Each element is replaced by folding with op all elements above and on its left (included).
+/// It requires an associative and commutative operation.
+/// Row pass and column pass are both scheduled adaptively.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_prefix_2d;
+/// let mut m = vec![1u32; 300 * 400];
+/// adaptive_prefix_2d(&mut m, 300, 400, |e1, e2| e1 + e2);
+/// assert_eq!(m[0], 1);
+/// assert_eq!(m[399], 400);
+/// assert_eq!(m[400 * 299], 300);
+/// assert_eq!(m[2 * 400 + 3], 3 * 4);
+/// assert_eq!(*m.last().unwrap(), 300 * 400);
+/// ```
+pub fn adaptive_prefix_2d<T, O>(matrix: &mut [T], rows: usize, cols: usize, op: O)
+where
+    T: Send + Sync + Clone,
+    O: Fn(&T, &T) -> T + Sync,
+{
+    assert_eq!(matrix.len(), rows * cols);
+    if matrix.is_empty() {
+        return;
+    }
+    let mut rows_slices: Vec<&mut [T]> = matrix.chunks_mut(cols).collect();
+    // row pass : each row is an independent sequential prefix
+    rows_slices
+        .as_mut_slice()
+        .into_adapt_iter()
+        .for_each(|row| {
+            let mut elements = row.iter_mut();
+            let mut c = elements.next().unwrap().clone();
+            for e in elements {
+                *e = op(e, &c);
+                c = e.clone();
+            }
+        });
+    // column pass : a prefix on rows, op being applied element-wise
+    prefix_in_place(rows_slices.as_mut_slice(), |row, previous_row| {
+        update_row(row, previous_row, &op)
+    });
+}
+
+fn update_row<T, O>(row: &mut [T], increment: &[T], op: &O)
 where
     T: Send + Sync + Clone,
     O: Fn(&T, &T) -> T + Sync,
 {
-    slice.into_adapt_iter().for_each(|e| *e = op(e, &increment))
+    row.iter_mut()
+        .zip(increment.iter())
+        .for_each(|(e, i)| *e = op(e, i))
 }
 
 // now the fully adaptive version
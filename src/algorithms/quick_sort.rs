@@ -0,0 +1,225 @@
+//! adaptive parallel quick sort (pattern defeating flavor).
+//! No temporary buffer.
+use crate::prelude::*;
+use crate::{depjoin, fuse_slices};
+use std::cmp::{max, min, Ordering};
+
+/// Partition given block sequentially, elements satisfying predicate first.
+/// Return the number of elements satisfying predicate.
+fn partition_block<T, P: Fn(&T) -> bool>(block: &mut [T], is_left: &P) -> usize {
+    let (mut left, mut right) = (0, block.len());
+    loop {
+        while left < right && is_left(&block[left]) {
+            left += 1;
+        }
+        while left < right && !is_left(&block[right - 1]) {
+            right -= 1;
+        }
+        if left >= right {
+            return left;
+        }
+        block.swap(left, right - 1);
+        left += 1;
+        right -= 1;
+    }
+}
+
+/// Fuse two contiguous partitioned slices.
+/// We swap the elements of the left slice's right part with the last
+/// elements of the right slice's left part.
+fn fuse_partitions<'a, T: Send + Sync>(
+    left: (&'a mut [T], usize),
+    right: (&'a mut [T], usize),
+) -> (&'a mut [T], usize) {
+    let (left_slice, left_middle) = left;
+    let (right_slice, right_middle) = right;
+    let swapped = min(left_slice.len() - left_middle, right_middle);
+    if swapped > 0 {
+        let misplaced_left: &mut [T] = &mut left_slice[left_middle..(left_middle + swapped)];
+        let misplaced_right: &mut [T] = &mut right_slice[(right_middle - swapped)..right_middle];
+        misplaced_left
+            .into_adapt_iter()
+            .zip(misplaced_right.into_adapt_iter())
+            .for_each(|(l, r)| std::mem::swap(l, r));
+    }
+    (
+        fuse_slices(left_slice, right_slice),
+        left_middle + right_middle,
+    )
+}
+
+/// Partition given slice in place, elements satisfying predicate first.
+/// Blocks are partitioned adaptively and then fused back together.
+/// Return the number of elements satisfying predicate.
+pub(crate) fn partition<T, P>(slice: &mut [T], is_left: P) -> usize
+where
+    T: Send + Sync,
+    P: Fn(&T) -> bool + Sync,
+{
+    slice
+        .map_reduce(
+            |block| {
+                let middle = partition_block(block, &is_left);
+                (block, middle)
+            },
+            fuse_partitions,
+        )
+        .1
+}
+
+/// Return index of median element of the three given ones.
+fn median_of_three<T, F>(slice: &[T], a: usize, b: usize, c: usize, compare: &F) -> usize
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let less = |i: usize, j: usize| compare(&slice[i], &slice[j]) == Ordering::Less;
+    if less(a, b) {
+        if less(b, c) {
+            b
+        } else if less(a, c) {
+            c
+        } else {
+            a
+        }
+    } else if less(a, c) {
+        a
+    } else if less(b, c) {
+        c
+    } else {
+        b
+    }
+}
+
+/// Choose a pivot : median of three or Tukey's ninther for large slices.
+pub(crate) fn choose_pivot<T, F>(slice: &[T], compare: &F) -> usize
+where
+    F: Fn(&T, &T) -> Ordering,
+{
+    let len = slice.len();
+    let (a, b, c) = (len / 4, len / 2, len / 4 * 3);
+    if len >= 50 {
+        let a = median_of_three(slice, a - 1, a, a + 1, compare);
+        let b = median_of_three(slice, b - 1, b, b + 1, compare);
+        let c = median_of_three(slice, c - 1, c, c + 1, compare);
+        median_of_three(slice, a, b, c, compare)
+    } else {
+        median_of_three(slice, a, b, c, compare)
+    }
+}
+
+/// Scatter some elements around in order to break patterns
+/// which lead to unbalanced partitions.
+fn break_patterns<T>(slice: &mut [T]) {
+    let len = slice.len();
+    if len >= 8 {
+        let position = len / 2;
+        for i in (position - 1)..=(position + 1) {
+            slice.swap(i, rand::random::<usize>() % len);
+        }
+    }
+}
+
+/// Sort slice recursively.
+/// All elements in slice are known to be greater or equal than `predecessor` (if any).
+/// `limit` is the number of bad partitions we still allow before switching to a sequential sort.
+fn quick_sort<T, F>(
+    slice: &mut [T],
+    predecessor: Option<&T>,
+    compare: &F,
+    block_size: usize,
+    limit: u32,
+) where
+    T: Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    let len = slice.len();
+    // for small slices or too many bad partitions we switch to the sequential algorithm
+    if len <= block_size || limit == 0 {
+        slice.sort_unstable_by(compare);
+        return;
+    }
+    let pivot_index = choose_pivot(slice, compare);
+    slice.swap(0, pivot_index);
+    let middle = {
+        let (pivot, remaining) = slice.split_first_mut().unwrap();
+        let pivot: &T = pivot;
+        if let Some(predecessor) = predecessor {
+            if compare(predecessor, pivot) != Ordering::Less {
+                // pivot is equal to predecessor.
+                // we isolate all elements equal to it and sort only the greater ones.
+                let equal = partition(remaining, |e| compare(pivot, e) != Ordering::Less);
+                quick_sort(
+                    &mut remaining[equal..],
+                    Some(pivot),
+                    compare,
+                    block_size,
+                    limit,
+                );
+                return;
+            }
+        }
+        partition(remaining, |e| compare(e, pivot) == Ordering::Less)
+    };
+    slice.swap(0, middle);
+    let (left, right) = slice.split_at_mut(middle);
+    let (pivot, right) = right.split_first_mut().unwrap();
+    let limit = if min(left.len(), right.len()) < len / 8 {
+        break_patterns(left);
+        break_patterns(right);
+        limit - 1
+    } else {
+        limit
+    };
+    depjoin(
+        || quick_sort(left, predecessor, compare, block_size, limit),
+        || quick_sort(right, Some(pivot), compare, block_size, limit),
+        |_, _| (),
+    );
+}
+
+/// Sort given slice using an adaptive version of pattern defeating quick sort.
+/// Sort is unstable and in place : no temporary buffer is allocated.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_sort_unstable;
+/// use rand::{thread_rng, Rng};
+///
+/// let v: Vec<u32> = (0..100_000).collect();
+/// let mut inverted_v: Vec<u32> = (0..100_000).rev().collect();
+/// let mut rng = thread_rng();
+/// let mut random_v: Vec<u32> = (0..100_000).collect();
+/// rng.shuffle(&mut random_v);
+/// adaptive_sort_unstable(&mut random_v);
+/// adaptive_sort_unstable(&mut inverted_v);
+/// assert_eq!(v, inverted_v);
+/// assert_eq!(v, random_v);
+/// ```
+pub fn adaptive_sort_unstable<T: Ord + Send + Sync>(slice: &mut [T]) {
+    adaptive_sort_unstable_by(slice, T::cmp)
+}
+
+/// Sort given slice with a comparator function using an adaptive version of
+/// pattern defeating quick sort.
+/// Sort is unstable and in place : no temporary buffer is allocated.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_sort_unstable_by;
+///
+/// let mut v: Vec<u32> = (0..100_000).map(|i| i % 100).collect();
+/// adaptive_sort_unstable_by(&mut v, |a, b| b.cmp(a));
+/// assert!(v.windows(2).all(|w| w[0] >= w[1]));
+/// ```
+pub fn adaptive_sort_unstable_by<T, F>(slice: &mut [T], compare: F)
+where
+    T: Send + Sync,
+    F: Fn(&T, &T) -> Ordering + Sync,
+{
+    // under this size we sort sequentially
+    let block_size = max(slice.len() / (2 * rayon::current_num_threads()), 20);
+    let limit = (slice.len() as f64).log(2.0).floor() as u32 + 1;
+    quick_sort(slice, None, &compare, block_size, limit)
+}
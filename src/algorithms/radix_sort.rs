@@ -0,0 +1,276 @@
+//! adaptive parallel radix sorts : LSD on integers and MSD on byte keys.
+use crate::adaptive_prefix;
+use crate::prelude::*;
+use std::mem::MaybeUninit;
+use std::ops::Range;
+use std::ptr;
+
+/// Under this size byte keys are sorted by comparisons.
+const MSD_SORT_SIZE: usize = 32;
+
+/// Keys we can radix sort directly.
+pub trait RadixKey: Copy + Send + Sync {
+    /// Number of bytes in the key.
+    const BYTES: usize;
+    /// Return byte of given rank (0 being the least significant).
+    /// Bytes order must be the same as the keys order.
+    fn byte(&self, rank: usize) -> u8;
+}
+
+macro_rules! unsigned_radix_key {
+    ($($t:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+                fn byte(&self, rank: usize) -> u8 {
+                    (*self >> (8 * rank)) as u8
+                }
+            }
+        )*
+    };
+}
+
+macro_rules! signed_radix_key {
+    ($($t:ty => $u:ty),*) => {
+        $(
+            impl RadixKey for $t {
+                const BYTES: usize = std::mem::size_of::<$t>();
+                fn byte(&self, rank: usize) -> u8 {
+                    // flip the sign bit so that negative numbers come first
+                    let flipped = (*self as $u) ^ (1 << (8 * Self::BYTES - 1));
+                    (flipped >> (8 * rank)) as u8
+                }
+            }
+        )*
+    };
+}
+
+unsigned_radix_key!(u8, u16, u32, u64, u128, usize);
+signed_radix_key!(i8 => u8, i16 => u16, i32 => u32, i64 => u64, i128 => u128, isize => usize);
+
+/// Raw output pointer we can share between threads.
+/// Each block of input writes to different positions.
+struct SharedOutput<T>(*mut MaybeUninit<T>);
+
+unsafe impl<T: Send> Send for SharedOutput<T> {}
+unsafe impl<T: Send> Sync for SharedOutput<T> {}
+
+/// Stable distribution of `input` into `output` according to given digit.
+/// Elements are moved bitwise : the caller decides which copy is owning them.
+/// Digits are computed once such that a `digit` function returning different digits
+/// on different calls cannot make us write outside of a bucket.
+/// Return the number of elements in each bucket
+/// or `None` (and do nothing) if all elements share the same digit.
+fn radix_pass<T, D>(
+    input: &[T],
+    output: &mut [MaybeUninit<T>],
+    buckets: usize,
+    digit: &D,
+) -> Option<Vec<usize>>
+where
+    T: Send + Sync,
+    D: Fn(&T) -> usize + Sync,
+{
+    let digits: Vec<u16> = input.into_adapt_iter().map(|e| digit(e) as u16).collect();
+    // histogram pass : one histogram for each task
+    let mut blocks: Vec<(Range<usize>, Vec<usize>)> = (0..input.len())
+        .cutting_fold(
+            || (None, vec![0; buckets]),
+            |(range, mut histogram): (Option<Range<usize>>, Vec<usize>), todo: Range<usize>| {
+                digits[todo.clone()]
+                    .iter()
+                    .for_each(|&d| histogram[d as usize] += 1);
+                let range = range.map_or_else(|| todo.clone(), |r| r.start..todo.end);
+                (Some(range), histogram)
+            },
+        )
+        .into_iter()
+        .filter_map(|(range, histogram)| range.map(|r| (r, histogram)))
+        .collect();
+
+    let blocks_number = blocks.len();
+    let counts: Vec<usize> = (0..buckets)
+        .map(|b| blocks.iter().map(|(_, h)| h[b]).sum::<usize>())
+        .collect();
+    if counts.contains(&input.len()) {
+        return None;
+    }
+
+    // offsets : prefix on all counts, bucket after bucket
+    let mut offsets: Vec<usize> = (0..buckets)
+        .flat_map(|b| blocks.iter().map(move |(_, h)| h[b]))
+        .collect();
+    adaptive_prefix(&mut offsets, |c1, c2| c1 + c2);
+    for (block_index, (_, histogram)) in blocks.iter_mut().enumerate() {
+        for (b, count) in histogram.iter_mut().enumerate() {
+            *count = offsets[b * blocks_number + block_index] - *count;
+        }
+    }
+
+    // scatter pass : each block writes its elements starting at its offsets
+    let output_pointer = SharedOutput(output.as_mut_ptr());
+    blocks
+        .as_mut_slice()
+        .into_adapt_iter()
+        .for_each(|(range, positions)| {
+            for (e, &d) in input[range.clone()].iter().zip(&digits[range.clone()]) {
+                let position = &mut positions[d as usize];
+                unsafe {
+                    ptr::copy_nonoverlapping(e, (*output_pointer.0.add(*position)).as_mut_ptr(), 1)
+                };
+                *position += 1;
+            }
+        });
+    Some(counts)
+}
+
+/// View initialized copyable elements as possibly uninitialized ones.
+fn as_uninit<T: Copy>(slice: &mut [T]) -> &mut [MaybeUninit<T>] {
+    unsafe { &mut *(slice as *mut [T] as *mut [MaybeUninit<T>]) }
+}
+
+/// LSD radix sort using `passes` passes.
+/// Digit of each element for a given pass is given by `digit`.
+fn radix_sort<T, D>(slice: &mut [T], passes: usize, buckets: usize, digit: D)
+where
+    T: Copy + Send + Sync,
+    D: Fn(&T, usize) -> usize + Sync,
+{
+    let len = slice.len();
+    if len <= 1 {
+        return;
+    }
+    let mut buffer = vec![slice[0]; len];
+    let mut data_in_buffer = false;
+    for pass in 0..passes {
+        let pass_digit = |e: &T| digit(e, pass);
+        let moved = if data_in_buffer {
+            radix_pass(&buffer, as_uninit(slice), buckets, &pass_digit)
+        } else {
+            radix_pass(slice, as_uninit(&mut buffer), buckets, &pass_digit)
+        };
+        if moved.is_some() {
+            data_in_buffer = !data_in_buffer;
+        }
+    }
+    if data_in_buffer {
+        slice
+            .into_adapt_iter()
+            .zip(buffer.as_slice().into_adapt_iter())
+            .for_each(|(d, s)| *d = *s);
+    }
+}
+
+/// MSD radix sort on byte keys, all keys sharing their first `depth` bytes.
+/// Elements are distributed into `buffer` and moved back before sorting each bucket,
+/// keys ending at `depth` being already in place.
+fn msd_radix_sort<T, F>(slice: &mut [T], buffer: &mut [MaybeUninit<T>], mut depth: usize, key: &F)
+where
+    T: Send + Sync,
+    F: Fn(&T) -> &[u8] + Sync,
+{
+    loop {
+        if slice.len() <= MSD_SORT_SIZE {
+            slice.sort_by(|e1, e2| key(e1)[depth..].cmp(&key(e2)[depth..]));
+            return;
+        }
+        // bucket 0 is for keys which are too short
+        let digit = |e: &T| key(e).get(depth).map_or(0, |&byte| byte as usize + 1);
+        match radix_pass(slice, buffer, 257, &digit) {
+            None => {
+                if digit(&slice[0]) == 0 {
+                    return;
+                }
+                depth += 1;
+            }
+            Some(counts) => {
+                // no user code runs while elements are moved back so nothing can be dropped twice
+                slice
+                    .into_adapt_iter()
+                    .zip(buffer.into_adapt_iter())
+                    .for_each(|(d, s)| unsafe { ptr::write(d, s.as_ptr().read()) });
+                let mut buckets = Vec::new();
+                let (mut remaining_slice, mut remaining_buffer) = (slice, buffer);
+                for (bucket, count) in counts.into_iter().enumerate() {
+                    let (bucket_slice, next_slice) = remaining_slice.split_at_mut(count);
+                    let (bucket_buffer, next_buffer) = remaining_buffer.split_at_mut(count);
+                    // keys of bucket 0 are all equal
+                    if bucket != 0 && count > 1 {
+                        buckets.push((bucket_slice, bucket_buffer));
+                    }
+                    remaining_slice = next_slice;
+                    remaining_buffer = next_buffer;
+                }
+                if !buckets.is_empty() {
+                    buckets.as_mut_slice().into_adapt_iter().for_each(
+                        |(bucket_slice, bucket_buffer)| {
+                            msd_radix_sort(bucket_slice, bucket_buffer, depth + 1, key)
+                        },
+                    );
+                }
+                return;
+            }
+        }
+    }
+}
+
+/// Sort given slice of integers using an adaptive version of LSD radix sort.
+/// Histograms and scattering are scheduled adaptively.
+/// Sort is stable.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_radix_sort;
+/// use rand::{thread_rng, Rng};
+///
+/// let v: Vec<u64> = (0..100_000).collect();
+/// let mut rng = thread_rng();
+/// let mut random_v: Vec<u64> = (0..100_000).collect();
+/// rng.shuffle(&mut random_v);
+/// adaptive_radix_sort(&mut random_v);
+/// assert_eq!(v, random_v);
+///
+/// let mut signed_v: Vec<i32> = (-50_000..50_000).rev().collect();
+/// adaptive_radix_sort(&mut signed_v);
+/// assert_eq!(signed_v, (-50_000..50_000).collect::<Vec<i32>>());
+/// ```
+pub fn adaptive_radix_sort<T: RadixKey>(slice: &mut [T]) {
+    radix_sort(slice, T::BYTES, 256, |e, pass| e.byte(pass) as usize)
+}
+
+/// Sort given slice using an adaptive version of MSD radix sort on the bytes keys
+/// extracted by `key`.
+/// Keys are compared lexicographically (a key comes before all its extensions).
+/// Buckets are only sorted further while their keys have bytes left
+/// and small buckets are sorted by comparisons.
+/// Sort is stable.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_radix_sort_by_key;
+///
+/// let words = vec!["radix", "sort", "", "adaptive", "rayon", "a", "ad", "sorting"];
+/// let mut sorted_words = words.clone();
+/// adaptive_radix_sort_by_key(&mut sorted_words, |w| w.as_bytes());
+/// let mut expected_words = words.clone();
+/// expected_words.sort();
+/// assert_eq!(sorted_words, expected_words);
+///
+/// let mut lines: Vec<String> = (0..100_000).rev().map(|i| format!("line {}", i)).collect();
+/// lines.push("x".repeat(10_000));
+/// adaptive_radix_sort_by_key(&mut lines, |l| l.as_bytes());
+/// assert_eq!(lines[0], "line 0");
+/// assert_eq!(lines[1], "line 1");
+/// assert_eq!(lines[2], "line 10");
+/// ```
+pub fn adaptive_radix_sort_by_key<T, F>(slice: &mut [T], key: F)
+where
+    T: Send + Sync,
+    F: Fn(&T) -> &[u8] + Sync,
+{
+    let mut buffer: Vec<MaybeUninit<T>> = Vec::with_capacity(slice.len());
+    unsafe { buffer.set_len(slice.len()) };
+    msd_radix_sort(slice, &mut buffer, 0, &key)
+}
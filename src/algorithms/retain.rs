@@ -0,0 +1,276 @@
+//! adaptive in place filtering : retain, dedup and stable partition.
+use crate::prelude::*;
+use crate::{fuse_slices, EdibleSliceMut};
+use std::cmp::min;
+use std::mem;
+use std::ptr;
+use std::slice;
+
+/// Blocks larger than this are partitioned recursively to bound temporary memory.
+const PARTITION_BUFFER_SIZE: usize = 1024;
+
+/// Run given compaction on blocks of the vector's content in parallel
+/// and fuse compacted blocks back together.
+/// Compacting a block moves kept elements to its start and drops all others.
+fn compact<T, C, F>(vector: &mut Vec<T>, compact_block: C, fuse: F)
+where
+    T: Send + Sync,
+    C: for<'a> Fn(&'a mut [T]) -> EdibleSliceMut<'a, T> + Sync,
+    F: for<'a> Fn(EdibleSliceMut<'a, T>, EdibleSliceMut<'a, T>) -> EdibleSliceMut<'a, T> + Sync,
+{
+    let len = vector.len();
+    if len == 0 {
+        return;
+    }
+    // if anything panics we leak elements instead of dropping them twice
+    unsafe {
+        vector.set_len(0);
+    }
+    let slice = unsafe { slice::from_raw_parts_mut(vector.as_mut_ptr(), len) };
+    let compacted = slice.map_reduce(compact_block, fuse);
+    let kept = compacted.previous_part(compacted.base_length()).len();
+    unsafe {
+        vector.set_len(kept);
+    }
+}
+
+/// Move to the start of the block all elements satisfying predicate and drop others.
+fn retain_block<'a, T, P>(block: &'a mut [T], predicate: &P) -> EdibleSliceMut<'a, T>
+where
+    P: Fn(&T) -> bool,
+{
+    let mut kept = 0;
+    for index in 0..block.len() {
+        unsafe {
+            let element = block.as_mut_ptr().add(index);
+            if predicate(&*element) {
+                ptr::copy(element, block.as_mut_ptr().add(kept), 1);
+                kept += 1;
+            } else {
+                ptr::drop_in_place(element);
+            }
+        }
+    }
+    EdibleSliceMut::with_used(block, kept)
+}
+
+/// Retain only elements satisfying given predicate.
+/// Order of kept elements is preserved and removed elements are dropped.
+/// No temporary buffer is allocated.
+/// If the predicate panics, all elements are leaked and the vector is left empty.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_retain;
+///
+/// let mut v: Vec<String> = (0..100_000).map(|i| i.to_string()).collect();
+/// adaptive_retain(&mut v, |s| s.ends_with('7'));
+/// let expected: Vec<String> = (0..10_000).map(|i| (i * 10 + 7).to_string()).collect();
+/// assert_eq!(v, expected);
+/// ```
+pub fn adaptive_retain<T, P>(vector: &mut Vec<T>, predicate: P)
+where
+    T: Send + Sync,
+    P: Fn(&T) -> bool + Sync,
+{
+    compact(
+        vector,
+        |block| retain_block(block, &predicate),
+        |left, right| left.fuse(right),
+    )
+}
+
+/// Move to the start of the block the first element of each run of equal elements
+/// and drop others.
+fn dedup_block<T: PartialEq>(block: &mut [T]) -> EdibleSliceMut<'_, T> {
+    let mut kept = min(block.len(), 1);
+    for index in 1..block.len() {
+        unsafe {
+            let element = block.as_mut_ptr().add(index);
+            if *element == *block.as_ptr().add(kept - 1) {
+                ptr::drop_in_place(element);
+            } else {
+                ptr::copy(element, block.as_mut_ptr().add(kept), 1);
+                kept += 1;
+            }
+        }
+    }
+    EdibleSliceMut::with_used(block, kept)
+}
+
+/// Fuse two deduplicated blocks, removing the first element of the right block
+/// if it is equal to the last kept element of the left block.
+fn fuse_dedup<'a, T: PartialEq + Send + Sync>(
+    left: EdibleSliceMut<'a, T>,
+    right: EdibleSliceMut<'a, T>,
+) -> EdibleSliceMut<'a, T> {
+    let right_kept = right.previous_part(right.base_length()).len();
+    let duplicate = {
+        let left_kept_part = left.previous_part(left.base_length());
+        let right_kept_part = right.previous_part(right.base_length());
+        match (left_kept_part.last(), right_kept_part.first()) {
+            (Some(last), Some(first)) => last == first,
+            _ => false,
+        }
+    };
+    if duplicate {
+        let (removed, right_slice) = right.slice().split_at_mut(1);
+        unsafe { ptr::drop_in_place(&mut removed[0]) };
+        left.fuse(EdibleSliceMut::with_used(removed, 0))
+            .fuse(EdibleSliceMut::with_used(right_slice, right_kept - 1))
+    } else {
+        left.fuse(right)
+    }
+}
+
+/// Remove consecutive repeated elements, keeping the first one of each run.
+/// Removed elements are dropped.
+/// No temporary buffer is allocated.
+/// If a comparison panics, all elements are leaked and the vector is left empty.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_dedup;
+///
+/// let mut v: Vec<u32> = (0..100_000).map(|i| i / 10).collect();
+/// adaptive_dedup(&mut v);
+/// assert_eq!(v, (0..10_000).collect::<Vec<u32>>());
+/// ```
+pub fn adaptive_dedup<T: PartialEq + Send + Sync>(vector: &mut Vec<T>) {
+    compact(vector, dedup_block, fuse_dedup)
+}
+
+/// Partition given small block sequentially and stably with temporary buffers,
+/// elements satisfying predicate first.
+/// Return the number of elements satisfying predicate.
+fn buffered_partition<T, P: Fn(&T) -> bool>(block: &mut [T], predicate: &P) -> usize {
+    // evaluate predicate first so that nothing is moved if it panics
+    let decisions: Vec<bool> = block.iter().map(predicate).collect();
+    let mut rejected: Vec<T> = Vec::with_capacity(block.len());
+    let mut kept = 0;
+    unsafe {
+        for (index, &keep) in decisions.iter().enumerate() {
+            let element = block.as_mut_ptr().add(index);
+            if keep {
+                ptr::copy(element, block.as_mut_ptr().add(kept), 1);
+                kept += 1;
+            } else {
+                rejected.push(ptr::read(element));
+            }
+        }
+        ptr::copy_nonoverlapping(
+            rejected.as_ptr(),
+            block.as_mut_ptr().add(kept),
+            rejected.len(),
+        );
+        rejected.set_len(0);
+    }
+    kept
+}
+
+/// Rotate given slice in place such that the element at `middle` becomes the first one.
+/// Blocks of elements are swapped in parallel.
+fn rotate_left<T: Send + Sync>(slice: &mut [T], middle: usize) {
+    let (mut slice, mut middle) = (slice, middle);
+    loop {
+        let (left_size, right_size) = (middle, slice.len() - middle);
+        // under this size it is faster to let the standard library move everything
+        if min(left_size, right_size) <= 1000 {
+            slice.rotate_left(middle);
+            return;
+        }
+        let swapped = min(left_size, right_size);
+        {
+            let (start, end) = slice.split_at_mut(slice.len() - swapped);
+            let start: &mut [T] = &mut start[..swapped];
+            start
+                .into_adapt_iter()
+                .zip(end.into_adapt_iter())
+                .for_each(|(a, b)| mem::swap(a, b));
+        }
+        if left_size <= right_size {
+            // the left part is now in place at the end
+            slice = &mut { slice }[..right_size];
+            middle = left_size;
+        } else {
+            // the right part is now in place at the start
+            slice = &mut { slice }[right_size..];
+            middle = left_size - right_size;
+        }
+    }
+}
+
+/// Partition given block sequentially and stably, elements satisfying predicate first.
+/// Large blocks are partitioned in place by halves which are fused with a rotation.
+/// Return the number of elements satisfying predicate.
+fn stable_partition_block<T, P>(block: &mut [T], predicate: &P) -> usize
+where
+    T: Send + Sync,
+    P: Fn(&T) -> bool,
+{
+    if block.len() <= PARTITION_BUFFER_SIZE {
+        return buffered_partition(block, predicate);
+    }
+    let middle = block.len() / 2;
+    let (left, right) = block.split_at_mut(middle);
+    let left_kept = stable_partition_block(left, predicate);
+    let right_kept = stable_partition_block(right, predicate);
+    rotate_left(
+        &mut block[left_kept..(middle + right_kept)],
+        middle - left_kept,
+    );
+    left_kept + right_kept
+}
+
+/// Fuse two contiguous stably partitioned slices.
+fn fuse_stable_partitions<'a, T: Send + Sync>(
+    left: (&'a mut [T], usize),
+    right: (&'a mut [T], usize),
+) -> (&'a mut [T], usize) {
+    let (left_slice, left_middle) = left;
+    let (right_slice, right_middle) = right;
+    let left_size = left_slice.len();
+    let slice = fuse_slices(left_slice, right_slice);
+    rotate_left(
+        &mut slice[left_middle..(left_size + right_middle)],
+        left_size - left_middle,
+    );
+    (slice, left_middle + right_middle)
+}
+
+/// Partition given slice in place, elements satisfying predicate first.
+/// Partition is stable : relative order is preserved on both sides.
+/// Blocks are partitioned in place, using temporary buffers of bounded size.
+/// Return the number of elements satisfying predicate.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_partition_in_place;
+///
+/// let mut v: Vec<u32> = (0..100_000).collect();
+/// let evens = adaptive_partition_in_place(&mut v, |&e| e % 2 == 0);
+/// assert_eq!(evens, 50_000);
+/// assert_eq!(&v[..evens], (0..50_000).map(|e| 2 * e).collect::<Vec<u32>>().as_slice());
+/// assert_eq!(&v[evens..], (0..50_000).map(|e| 2 * e + 1).collect::<Vec<u32>>().as_slice());
+/// ```
+pub fn adaptive_partition_in_place<T, P>(slice: &mut [T], predicate: P) -> usize
+where
+    T: Send + Sync,
+    P: Fn(&T) -> bool + Sync,
+{
+    if slice.is_empty() {
+        return 0;
+    }
+    slice
+        .map_reduce(
+            |block| {
+                let middle = stable_partition_block(block, &predicate);
+                (block, middle)
+            },
+            fuse_stable_partitions,
+        )
+        .1
+}
@@ -0,0 +1,285 @@
+//! adaptive scans fusing block summaries : maximum subarray, longest run, brackets balance.
+use crate::prelude::*;
+use std::ops::{Add, Range};
+
+/// Summarize all blocks of indices `0..length` and fuse summaries, in order.
+fn summarize<S, B, F>(length: usize, summarize_block: B, fuse: F) -> Option<S>
+where
+    S: Send + Sync,
+    B: Fn(Range<usize>) -> S + Sync,
+    F: Fn(S, S) -> S + Sync,
+{
+    if length == 0 {
+        return None;
+    }
+    let fuse_options = |summary1: Option<S>, summary2: Option<S>| match (summary1, summary2) {
+        (Some(summary1), Some(summary2)) => Some(fuse(summary1, summary2)),
+        (summary1, None) => summary1,
+        (None, summary2) => summary2,
+    };
+    (0..length)
+        .partial_fold(
+            || None,
+            |summary, range, limit| {
+                let (todo, remaining) = range.divide_at(limit);
+                let block_summary = summarize_block(todo);
+                (fuse_options(summary, Some(block_summary)), remaining)
+            },
+        )
+        .reduce(fuse_options)
+}
+
+/// What we need to know about a block to find maximum subarrays across blocks.
+#[derive(Debug, Clone)]
+struct SubarraySums<T> {
+    total: T,
+    // best sum of a prefix and where it ends
+    prefix: (T, usize),
+    // best sum of a suffix and where it starts
+    suffix: (T, usize),
+    best: (T, Range<usize>),
+}
+
+impl<T: Copy + PartialOrd + Add<Output = T>> SubarraySums<T> {
+    /// Kadane's algorithm on a non empty block starting at `offset`.
+    fn new(block: &[T], offset: usize) -> Self {
+        let mut total = block[0];
+        let mut prefix = (total, offset + 1);
+        // best sum ending at current position and where it starts
+        let mut current = (total, offset);
+        let mut best = (total, offset..offset + 1);
+        for (position, &e) in (offset..).zip(block.iter()).skip(1) {
+            total = total + e;
+            if total > prefix.0 {
+                prefix = (total, position + 1);
+            }
+            let extended = current.0 + e;
+            current = if extended > e {
+                (extended, current.1)
+            } else {
+                (e, position)
+            };
+            if current.0 > best.0 {
+                best = (current.0, current.1..position + 1);
+            }
+        }
+        SubarraySums {
+            total,
+            prefix,
+            suffix: current,
+            best,
+        }
+    }
+    fn fuse(self, other: Self) -> Self {
+        let extended_prefix = (self.total + other.prefix.0, other.prefix.1);
+        let extended_suffix = (self.suffix.0 + other.total, self.suffix.1);
+        let middle = (
+            self.suffix.0 + other.prefix.0,
+            self.suffix.1..other.prefix.1,
+        );
+        let mut best = self.best;
+        for candidate in [middle, other.best] {
+            if candidate.0 > best.0 {
+                best = candidate;
+            }
+        }
+        SubarraySums {
+            total: self.total + other.total,
+            prefix: if extended_prefix.0 > self.prefix.0 {
+                extended_prefix
+            } else {
+                self.prefix
+            },
+            suffix: if extended_suffix.0 > other.suffix.0 {
+                extended_suffix
+            } else {
+                other.suffix
+            },
+            best,
+        }
+    }
+}
+
+/// Return the largest sum of a non empty contiguous subarray with its range of indices.
+/// Return `None` on an empty slice.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_max_subarray;
+///
+/// let v: Vec<i64> = (0..100_000).map(|i| if i % 1000 == 500 { 600 } else { -1 }).collect();
+/// let (sum, range) = adaptive_max_subarray(&v).unwrap();
+/// assert_eq!(sum, 600);
+/// assert_eq!(v[range].iter().sum::<i64>(), 600);
+/// let (sum, range) = adaptive_max_subarray(&[-3, -2, -5]).unwrap();
+/// assert_eq!((sum, range), (-2, 1..2));
+/// ```
+pub fn adaptive_max_subarray<T>(slice: &[T]) -> Option<(T, Range<usize>)>
+where
+    T: Copy + PartialOrd + Add<Output = T> + Send + Sync,
+{
+    summarize(
+        slice.len(),
+        |range| SubarraySums::new(&slice[range.clone()], range.start),
+        SubarraySums::fuse,
+    )
+    .map(|sums| sums.best)
+}
+
+/// What we need to know about a block to find longest runs across blocks.
+#[derive(Debug, Clone)]
+struct Runs {
+    range: Range<usize>,
+    // length of the run starting the block
+    prefix: usize,
+    // length of the run ending the block
+    suffix: usize,
+    best: Range<usize>,
+}
+
+impl Runs {
+    fn new<T, M: Fn(&T, &T) -> bool>(slice: &[T], range: Range<usize>, matching: &M) -> Self {
+        let mut prefix = None;
+        let mut run_start = range.start;
+        let mut best = range.start..range.start + 1;
+        for position in (range.start + 1)..range.end {
+            if !matching(&slice[position - 1], &slice[position]) {
+                prefix = prefix.or(Some(position - range.start));
+                run_start = position;
+            }
+            if position + 1 - run_start > best.len() {
+                best = run_start..position + 1;
+            }
+        }
+        Runs {
+            prefix: prefix.unwrap_or_else(|| range.len()),
+            suffix: range.end - run_start,
+            range,
+            best,
+        }
+    }
+    fn fuse<T, M: Fn(&T, &T) -> bool>(self, other: Self, slice: &[T], matching: &M) -> Self {
+        let connected = matching(&slice[self.range.end - 1], &slice[other.range.start]);
+        let mut best = self.best.clone();
+        if connected {
+            let middle = (self.range.end - self.suffix)..(other.range.start + other.prefix);
+            if middle.len() > best.len() {
+                best = middle;
+            }
+        }
+        if other.best.len() > best.len() {
+            best = other.best;
+        }
+        Runs {
+            prefix: if connected && self.prefix == self.range.len() {
+                self.prefix + other.prefix
+            } else {
+                self.prefix
+            },
+            suffix: if connected && other.suffix == other.range.len() {
+                self.suffix + other.suffix
+            } else {
+                other.suffix
+            },
+            range: self.range.start..other.range.end,
+            best,
+        }
+    }
+}
+
+/// Return the range of indices of the first longest run of consecutive equal elements.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_longest_run;
+///
+/// let v: Vec<u32> = (0..100_000).map(|i| if i >= 70_000 && i < 70_100 { 0 } else { i }).collect();
+/// assert_eq!(adaptive_longest_run(&v), 70_000..70_100);
+/// ```
+pub fn adaptive_longest_run<T: PartialEq + Sync>(slice: &[T]) -> Range<usize> {
+    adaptive_longest_run_by(slice, |a, b| a == b)
+}
+
+/// Return the range of indices of the first longest run of elements
+/// where each one is matching the previous one.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_longest_run_by;
+///
+/// // longest increasing run
+/// let v: Vec<u32> = (0..100_000).map(|i| i % 1000 + if i >= 50_000 { 1 } else { 0 }).collect();
+/// assert_eq!(adaptive_longest_run_by(&v, |a, b| a < b), 0..1000);
+/// ```
+pub fn adaptive_longest_run_by<T, M>(slice: &[T], matching: M) -> Range<usize>
+where
+    T: Sync,
+    M: Fn(&T, &T) -> bool + Sync,
+{
+    summarize(
+        slice.len(),
+        |range| Runs::new(slice, range, &matching),
+        |runs1, runs2| runs1.fuse(runs2, slice, &matching),
+    )
+    .map(|runs| runs.best)
+    .unwrap_or(0..0)
+}
+
+/// Unmatched brackets of a block.
+#[derive(Debug, Clone, Copy, Default)]
+struct Brackets {
+    closing: usize,
+    opening: usize,
+}
+
+impl Brackets {
+    fn fuse(self, other: Self) -> Self {
+        let matched = if self.opening < other.closing {
+            self.opening
+        } else {
+            other.closing
+        };
+        Brackets {
+            closing: self.closing + other.closing - matched,
+            opening: self.opening - matched + other.opening,
+        }
+    }
+}
+
+/// Return the numbers of unmatched closing and opening brackets.
+/// Brackets are balanced if both are 0.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_bracket_balance;
+///
+/// let text = "(a(b)c)".repeat(100_000);
+/// assert_eq!(adaptive_bracket_balance(text.as_bytes(), b'(', b')'), (0, 0));
+/// let text = format!("){}((", text);
+/// assert_eq!(adaptive_bracket_balance(text.as_bytes(), b'(', b')'), (1, 2));
+/// ```
+pub fn adaptive_bracket_balance(bytes: &[u8], open: u8, close: u8) -> (usize, usize) {
+    if bytes.is_empty() {
+        return (0, 0);
+    }
+    let brackets = bytes
+        .into_adapt_iter()
+        .fold(Brackets::default, |mut brackets, &byte| {
+            if byte == open {
+                brackets.opening += 1;
+            } else if byte == close {
+                if brackets.opening > 0 {
+                    brackets.opening -= 1;
+                } else {
+                    brackets.closing += 1;
+                }
+            }
+            brackets
+        })
+        .reduce(Brackets::fuse);
+    (brackets.closing, brackets.opening)
+}
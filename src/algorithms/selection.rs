@@ -0,0 +1,119 @@
+//! adaptive order statistics : selection and top-k.
+use super::quick_sort::{choose_pivot, partition};
+use crate::prelude::*;
+use std::cmp::{max, Reverse};
+use std::collections::BinaryHeap;
+
+/// Reorder given slice such that element at index `k` is the one which would be there
+/// if the slice was sorted.
+/// All elements before it are smaller or equal and all elements after it are greater or equal.
+/// Partitioning is done in parallel.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_select_nth;
+/// use rand::{thread_rng, Rng};
+///
+/// let mut rng = thread_rng();
+/// let mut latencies: Vec<u32> = (0..100_000).map(|i| i % 1000).collect();
+/// rng.shuffle(&mut latencies);
+/// // let's get the median and the 99th percentile
+/// adaptive_select_nth(&mut latencies, 50_000);
+/// assert_eq!(latencies[50_000], 500);
+/// assert!(latencies[..50_000].iter().all(|&l| l <= 500));
+/// adaptive_select_nth(&mut latencies, 99_000);
+/// assert_eq!(latencies[99_000], 990);
+/// ```
+pub fn adaptive_select_nth<T: Ord + Send + Sync>(slice: &mut [T], k: usize) {
+    assert!(k < slice.len());
+    // under this size we select sequentially
+    let block_size = max(slice.len() / (2 * rayon::current_num_threads()), 20);
+    let mut slice = slice;
+    let mut k = k;
+    loop {
+        if slice.len() <= block_size {
+            slice.sort_unstable();
+            return;
+        }
+        let pivot_index = choose_pivot(slice, &T::cmp);
+        slice.swap(0, pivot_index);
+        let (smaller, equal) = {
+            let (pivot, remaining) = slice.split_first_mut().unwrap();
+            let pivot: &T = pivot;
+            let smaller = partition(remaining, |e| e < pivot);
+            let equal = if k > smaller {
+                // isolate all elements equal to the pivot to avoid quadratic work on duplicates
+                partition(&mut remaining[smaller..], |e| e == pivot)
+            } else {
+                0
+            };
+            (smaller, equal)
+        };
+        slice.swap(0, smaller);
+        if k < smaller {
+            slice = &mut { slice }[..smaller];
+        } else if k <= smaller + equal {
+            return;
+        } else {
+            slice = &mut { slice }[(smaller + equal + 1)..];
+            k -= smaller + equal + 1;
+        }
+    }
+}
+
+/// Add element to heap, keeping only the `k` largest elements.
+fn push_bounded<T: Ord>(heap: &mut BinaryHeap<Reverse<T>>, element: T, k: usize) {
+    if heap.len() < k {
+        heap.push(Reverse(element));
+    } else if let Some(mut smallest) = heap.peek_mut() {
+        if smallest.0 < element {
+            *smallest = Reverse(element);
+        }
+    }
+}
+
+/// Return the `k` largest elements of given adaptive iterator, largest first.
+/// Each task keeps a heap of at most `k` elements and heaps are merged when reducing.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_top_k;
+/// use rayon_adaptive::prelude::*;
+///
+/// let v: Vec<u32> = (0..100_000).map(|i| (i * 7919) % 100_000).collect();
+/// let top = adaptive_top_k(v.into_adapt_iter().cloned(), 5);
+/// assert_eq!(top, vec![99_999, 99_998, 99_997, 99_996, 99_995]);
+/// ```
+pub fn adaptive_top_k<I>(iter: I, k: usize) -> Vec<I::Item>
+where
+    I: AdaptiveIterator,
+    I::Item: Ord + Send + Sync,
+{
+    if k == 0 || iter.base_length() == 0 {
+        return Vec::new();
+    }
+    iter.partial_fold(BinaryHeap::new, |mut heap, i, limit| {
+        let (todo, remaining) = i.divide_at(limit);
+        for element in todo {
+            push_bounded(&mut heap, element, k);
+        }
+        (heap, remaining)
+    })
+    .reduce(|heap1, heap2| {
+        let (mut large_heap, small_heap) = if heap1.len() >= heap2.len() {
+            (heap1, heap2)
+        } else {
+            (heap2, heap1)
+        };
+        for Reverse(element) in small_heap {
+            push_bounded(&mut large_heap, element, k);
+        }
+        large_heap
+    })
+    .into_sorted_vec()
+    .into_iter()
+    .map(|Reverse(element)| element)
+    .collect()
+}
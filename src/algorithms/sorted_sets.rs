@@ -0,0 +1,251 @@
+//! adaptive set operations on sorted slices without duplicates.
+use super::merge_sort::merge_split;
+use crate::prelude::*;
+use crate::traits::BlockedPower;
+use std::cmp::{min, Ordering};
+use std::iter::Peekable;
+use std::slice;
+
+/// Which elements do we keep.
+#[derive(Debug, Clone, Copy)]
+enum SetOperation {
+    Union,
+    Intersection,
+    Difference,
+    SymmetricDifference,
+}
+
+impl SetOperation {
+    /// Do we keep elements only in left set, only in right set, in both sets ?
+    fn keeps(self) -> (bool, bool, bool) {
+        match self {
+            SetOperation::Union => (true, true, true),
+            SetOperation::Intersection => (false, false, true),
+            SetOperation::Difference => (true, false, false),
+            SetOperation::SymmetricDifference => (true, true, false),
+        }
+    }
+}
+
+/// Parallel iterator on the result of a set operation.
+struct SortedSets<'a, T: 'a> {
+    left: &'a [T],
+    right: &'a [T],
+    operation: SetOperation,
+}
+
+impl<'a, T: 'a + Ord + Sync> Divisible for SortedSets<'a, T> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        // an element present in both sets cannot be divided
+        if self.left.len() == 1 && self.right.len() == 1 && self.left[0] == self.right[0] {
+            1
+        } else {
+            self.left.len() + self.right.len()
+        }
+    }
+    fn divide(self) -> (Self, Self) {
+        let compare = T::cmp;
+        // cut around the middle of the largest set, equal elements go in the right part
+        let ((l1, _, _), (r1, _, _)) = if self.left.len() > self.right.len() {
+            merge_split(self.left, self.right, &compare)
+        } else {
+            let (r, l) = merge_split(self.right, self.left, &compare);
+            (l, r)
+        };
+        if l1.is_empty() && r1.is_empty() {
+            // only happens with one element in each set
+            let middle = self.base_length() / 2;
+            self.divide_at(middle)
+        } else {
+            self.divide_at_positions(l1.len(), r1.len())
+        }
+    }
+}
+
+impl<'a, T: 'a + Ord + Sync> DivisibleIntoBlocks for SortedSets<'a, T> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = (self.left, self.right);
+        let index = min(index, left.len() + right.len());
+        // co-ranking : find how many elements of each set come first in the merged sets
+        let (mut start, mut end) = (index.saturating_sub(right.len()), min(index, left.len()));
+        while start < end {
+            let left_index = (start + end) / 2;
+            if left[left_index] <= right[index - left_index - 1] {
+                start = left_index + 1;
+            } else {
+                end = left_index;
+            }
+        }
+        let left_index = start;
+        let mut right_index = index - left_index;
+        // an element present in both sets should not be separated
+        if left_index > 0 && right_index < right.len() && left[left_index - 1] == right[right_index]
+        {
+            right_index += 1;
+        }
+        self.divide_at_positions(left_index, right_index)
+    }
+}
+
+impl<'a, T: 'a> SortedSets<'a, T> {
+    fn divide_at_positions(self, left_index: usize, right_index: usize) -> (Self, Self) {
+        let (left1, left2) = self.left.split_at(left_index);
+        let (right1, right2) = self.right.split_at(right_index);
+        (
+            SortedSets {
+                left: left1,
+                right: right1,
+                operation: self.operation,
+            },
+            SortedSets {
+                left: left2,
+                right: right2,
+                operation: self.operation,
+            },
+        )
+    }
+}
+
+/// Sequential iterator on the result of a set operation.
+struct SortedSetsIter<'a, T: 'a> {
+    left: Peekable<slice::Iter<'a, T>>,
+    right: Peekable<slice::Iter<'a, T>>,
+    keeps: (bool, bool, bool),
+}
+
+impl<'a, T: 'a + Ord + Clone> Iterator for SortedSetsIter<'a, T> {
+    type Item = T;
+    fn next(&mut self) -> Option<T> {
+        let (keep_left, keep_right, keep_both) = self.keeps;
+        loop {
+            let (keep, element) = match (self.left.peek(), self.right.peek()) {
+                (None, None) => return None,
+                (Some(_), None) => (keep_left, self.left.next()),
+                (None, Some(_)) => (keep_right, self.right.next()),
+                (Some(l), Some(r)) => match l.cmp(r) {
+                    Ordering::Less => (keep_left, self.left.next()),
+                    Ordering::Greater => (keep_right, self.right.next()),
+                    Ordering::Equal => {
+                        self.right.next();
+                        (keep_both, self.left.next())
+                    }
+                },
+            };
+            if keep {
+                return element.cloned();
+            }
+        }
+    }
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        (0, Some(self.left.len() + self.right.len()))
+    }
+}
+
+impl<'a, T: 'a + Ord + Clone> IntoIterator for SortedSets<'a, T> {
+    type Item = T;
+    type IntoIter = SortedSetsIter<'a, T>;
+    fn into_iter(self) -> Self::IntoIter {
+        SortedSetsIter {
+            left: self.left.iter().peekable(),
+            right: self.right.iter().peekable(),
+            keeps: self.operation.keeps(),
+        }
+    }
+}
+
+impl<'a, T: 'a + Ord + Clone + Send + Sync> AdaptiveIterator for SortedSets<'a, T> {}
+
+fn set_operation<T>(left: &[T], right: &[T], operation: SetOperation) -> Vec<T>
+where
+    T: Ord + Clone + Send + Sync,
+{
+    if left.is_empty() && right.is_empty() {
+        return Vec::new();
+    }
+    SortedSets {
+        left,
+        right,
+        operation,
+    }
+    .collect()
+}
+
+/// Return all elements present in `left` or `right`.
+/// Both inputs must be sorted and without duplicates.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_union;
+///
+/// let evens: Vec<u32> = (0..100_000).map(|i| 2 * i).collect();
+/// let threes: Vec<u32> = (0..100_000).map(|i| 3 * i).collect();
+/// let union = adaptive_union(&evens, &threes);
+/// let expected: Vec<u32> = (0..300_000)
+///     .filter(|i| (i % 2 == 0 && *i < 200_000) || i % 3 == 0)
+///     .collect();
+/// assert_eq!(union, expected);
+/// ```
+pub fn adaptive_union<T: Ord + Clone + Send + Sync>(left: &[T], right: &[T]) -> Vec<T> {
+    set_operation(left, right, SetOperation::Union)
+}
+
+/// Return all elements present in both `left` and `right`.
+/// Both inputs must be sorted and without duplicates.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_intersection;
+///
+/// let evens: Vec<u32> = (0..100_000).map(|i| 2 * i).collect();
+/// let threes: Vec<u32> = (0..100_000).map(|i| 3 * i).collect();
+/// let intersection = adaptive_intersection(&evens, &threes);
+/// let expected: Vec<u32> = (0..200_000).filter(|i| i % 6 == 0).collect();
+/// assert_eq!(intersection, expected);
+/// ```
+pub fn adaptive_intersection<T: Ord + Clone + Send + Sync>(left: &[T], right: &[T]) -> Vec<T> {
+    set_operation(left, right, SetOperation::Intersection)
+}
+
+/// Return all elements present in `left` but not in `right`.
+/// Both inputs must be sorted and without duplicates.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_difference;
+///
+/// let evens: Vec<u32> = (0..100_000).map(|i| 2 * i).collect();
+/// let threes: Vec<u32> = (0..100_000).map(|i| 3 * i).collect();
+/// let difference = adaptive_difference(&evens, &threes);
+/// let expected: Vec<u32> = (0..200_000).filter(|i| i % 2 == 0 && i % 3 != 0).collect();
+/// assert_eq!(difference, expected);
+/// ```
+pub fn adaptive_difference<T: Ord + Clone + Send + Sync>(left: &[T], right: &[T]) -> Vec<T> {
+    set_operation(left, right, SetOperation::Difference)
+}
+
+/// Return all elements present in exactly one of `left` and `right`.
+/// Both inputs must be sorted and without duplicates.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_symmetric_difference;
+///
+/// let evens: Vec<u32> = (0..100_000).map(|i| 2 * i).collect();
+/// let threes: Vec<u32> = (0..100_000).map(|i| 3 * i).collect();
+/// let difference = adaptive_symmetric_difference(&evens, &threes);
+/// let expected: Vec<u32> = (0..300_000)
+///     .filter(|i| (i % 2 == 0 && *i < 200_000) != (i % 3 == 0))
+///     .collect();
+/// assert_eq!(difference, expected);
+/// ```
+pub fn adaptive_symmetric_difference<T: Ord + Clone + Send + Sync>(
+    left: &[T],
+    right: &[T],
+) -> Vec<T> {
+    set_operation(left, right, SetOperation::SymmetricDifference)
+}
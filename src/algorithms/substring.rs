@@ -0,0 +1,151 @@
+//! adaptive substring search.
+use crate::prelude::*;
+use crate::traits::BlockedPower;
+use std::cmp::min;
+
+/// Parallel iterator on occurrences of `needle` starting in a range of positions.
+/// We divide on starting positions so blocks of bytes overlap by `needle.len() - 1`
+/// and each occurrence is found exactly once.
+struct Occurrences<'a> {
+    haystack: &'a [u8],
+    needle: &'a [u8],
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Divisible for Occurrences<'a> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        self.end - self.start
+    }
+    fn divide(self) -> (Self, Self) {
+        let middle = self.base_length() / 2;
+        self.divide_at(middle)
+    }
+}
+
+impl<'a> DivisibleIntoBlocks for Occurrences<'a> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let middle = min(self.start + index, self.end);
+        (
+            Occurrences {
+                haystack: self.haystack,
+                needle: self.needle,
+                start: self.start,
+                end: middle,
+            },
+            Occurrences {
+                haystack: self.haystack,
+                needle: self.needle,
+                start: middle,
+                end: self.end,
+            },
+        )
+    }
+}
+
+/// Sequential iterator on occurrences of `needle` starting in a range of positions.
+struct OccurrencesIter<'a> {
+    haystack: &'a [u8],
+    needle: &'a [u8],
+    start: usize,
+    end: usize,
+}
+
+impl<'a> Iterator for OccurrencesIter<'a> {
+    type Item = usize;
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.start >= self.end {
+            return None;
+        }
+        let needle = self.needle;
+        let window = &self.haystack[self.start..self.end + needle.len() - 1];
+        match window.windows(needle.len()).position(|w| w == needle) {
+            Some(offset) => {
+                let position = self.start + offset;
+                self.start = position + 1;
+                Some(position)
+            }
+            None => {
+                self.start = self.end;
+                None
+            }
+        }
+    }
+}
+
+impl<'a> IntoIterator for Occurrences<'a> {
+    type Item = usize;
+    type IntoIter = OccurrencesIter<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        OccurrencesIter {
+            haystack: self.haystack,
+            needle: self.needle,
+            start: self.start,
+            end: self.end,
+        }
+    }
+}
+
+impl<'a> AdaptiveIterator for Occurrences<'a> {}
+
+/// Parallel iterator on all occurrences. `needle` is not empty.
+fn occurrences<'a>(haystack: &'a [u8], needle: &'a [u8]) -> Occurrences<'a> {
+    Occurrences {
+        haystack,
+        needle,
+        start: 0,
+        end: (haystack.len() + 1).saturating_sub(needle.len()),
+    }
+}
+
+/// Return the starting positions of all (possibly overlapping) occurrences of `needle`,
+/// in increasing order.
+/// An empty needle occurs at all positions.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_find_all;
+///
+/// let logs = b"ok\nok\nERROR\nok\n".repeat(10_000);
+/// let errors = adaptive_find_all(&logs, b"ERROR");
+/// assert_eq!(errors.len(), 10_000);
+/// assert!(errors.iter().enumerate().all(|(i, &p)| p == 15 * i + 6));
+/// assert_eq!(adaptive_find_all(b"aaaa", b"aa"), vec![0, 1, 2]);
+/// ```
+pub fn adaptive_find_all(haystack: &[u8], needle: &[u8]) -> Vec<usize> {
+    if needle.is_empty() {
+        return (0..=haystack.len()).collect();
+    }
+    let occurrences = occurrences(haystack, needle);
+    if occurrences.base_length() == 0 {
+        return Vec::new();
+    }
+    occurrences.collect()
+}
+
+/// Return the starting position of the first occurrence of `needle`, if any.
+/// Blocks are searched by increasing sizes so we stop early
+/// when the occurrence is close to the start.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_find_first;
+///
+/// let logs = b"ok\n".repeat(1_000_000);
+/// assert_eq!(adaptive_find_first(&logs, b"ERROR"), None);
+/// let logs = [&logs[..], b"ERROR", &logs[..], b"ERROR"].concat();
+/// assert_eq!(adaptive_find_first(&logs, b"ERROR"), Some(3_000_000));
+/// ```
+pub fn adaptive_find_first(haystack: &[u8], needle: &[u8]) -> Option<usize> {
+    if needle.is_empty() {
+        return Some(0);
+    }
+    let occurrences = occurrences(haystack, needle);
+    if occurrences.base_length() == 0 {
+        return None;
+    }
+    occurrences.find_first(|_| true)
+}
@@ -0,0 +1,79 @@
+//! adaptive utf-8 validation.
+use crate::prelude::*;
+use crate::utils::powers;
+use std::cmp::{max, min};
+use std::ops::Range;
+use std::str::{self, Utf8Error};
+
+fn is_continuation(byte: u8) -> bool {
+    byte & 0xC0 == 0x80
+}
+
+/// First index not below `index` which is 0 or not a continuation byte.
+fn char_start(bytes: &[u8], index: usize) -> usize {
+    if index == 0 {
+        return 0;
+    }
+    bytes[index..]
+        .iter()
+        .position(|&b| !is_continuation(b))
+        .map_or(bytes.len(), |p| index + p)
+}
+
+/// Validate all chars starting inside given range of indices
+/// and return the position of the first error, if any.
+/// Since both ends are moved to char starts, ranges of contiguous blocks stay contiguous
+/// and each block is validated exactly as sequential code would do.
+fn first_error(bytes: &[u8], range: Range<usize>) -> Option<usize> {
+    let start = char_start(bytes, range.start);
+    let end = char_start(bytes, range.end);
+    str::from_utf8(&bytes[start..end])
+        .err()
+        .map(|error| start + error.valid_up_to())
+}
+
+/// Convert a slice of bytes to a string slice, like `std::str::from_utf8`.
+///
+/// Blocks of increasing sizes are validated in parallel and we stop on the first invalid one,
+/// like `find_first`.
+/// On invalid input the error is re-computed sequentially on the valid prefix
+/// since `Utf8Error` can only be built by the standard library.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_from_utf8;
+/// use rayon_adaptive::prelude::*;
+///
+/// let mut bytes = "l'été à Grenoble\n".repeat(100_000).into_bytes();
+/// let text = adaptive_from_utf8(&bytes).unwrap();
+/// assert_eq!(text.adapt_lines().filter(|l| l.contains('é')).count(), 100_000);
+/// bytes[1_000_000] = 0xFF;
+/// let error = adaptive_from_utf8(&bytes).unwrap_err();
+/// assert_eq!(error, std::str::from_utf8(&bytes).unwrap_err());
+/// assert_eq!(error.valid_up_to(), 1_000_000);
+/// ```
+pub fn adaptive_from_utf8(bytes: &[u8]) -> Result<&str, Utf8Error> {
+    let len = bytes.len();
+    if len == 0 {
+        return Ok("");
+    }
+    let base_size = min(max((len as f64).log(2.0).ceil() as usize, 1), len);
+    let error = (0..len)
+        .by_blocks(powers(base_size))
+        .partial_fold(
+            || None,
+            |error, range, limit| {
+                let (todo, remaining) = range.divide_at(limit);
+                (error.or_else(|| first_error(bytes, todo)), remaining)
+            },
+        )
+        .into_iter()
+        .flatten()
+        .next();
+    match error {
+        // a char is at most 4 bytes long so we see the same error as on the whole input
+        Some(position) => str::from_utf8(&bytes[..min(position + 4, len)]),
+        None => Ok(unsafe { str::from_utf8_unchecked(bytes) }),
+    }
+}
@@ -0,0 +1,93 @@
+//! adaptive word count.
+use crate::prelude::*;
+
+/// Whitespaces of the C locale.
+fn is_space(byte: u8) -> bool {
+    matches!(byte, b' ' | b'\t' | b'\n' | b'\r' | 0x0B | 0x0C)
+}
+
+/// Counts on a block of bytes.
+/// We also remember if the block starts or ends inside a word
+/// since a word straddling two blocks is only counted once.
+#[derive(Debug, Clone, Copy, Default)]
+struct WordCount {
+    lines: usize,
+    words: usize,
+    bytes: usize,
+    chars: usize,
+    starts_in_word: bool,
+    ends_in_word: bool,
+}
+
+impl WordCount {
+    fn new(bytes: &[u8]) -> Self {
+        let mut in_word = false;
+        let mut count = WordCount {
+            bytes: bytes.len(),
+            starts_in_word: bytes.first().is_some_and(|&b| !is_space(b)),
+            ..Default::default()
+        };
+        for &byte in bytes {
+            if byte == b'\n' {
+                count.lines += 1;
+            }
+            // all bytes except utf-8 continuation bytes start a char
+            if byte & 0xC0 != 0x80 {
+                count.chars += 1;
+            }
+            let space = is_space(byte);
+            if !space && !in_word {
+                count.words += 1;
+            }
+            in_word = !space;
+        }
+        count.ends_in_word = in_word;
+        count
+    }
+    fn fuse(self, other: Self) -> Self {
+        if self.bytes == 0 {
+            return other;
+        }
+        if other.bytes == 0 {
+            return self;
+        }
+        let straddling = self.ends_in_word && other.starts_in_word;
+        WordCount {
+            lines: self.lines + other.lines,
+            words: self.words + other.words - if straddling { 1 } else { 0 },
+            bytes: self.bytes + other.bytes,
+            chars: self.chars + other.chars,
+            starts_in_word: self.starts_in_word,
+            ends_in_word: other.ends_in_word,
+        }
+    }
+}
+
+/// Return the numbers of lines, words, bytes and chars like `wc`.
+///
+/// Lines are counted as newlines and words are separated by ascii whitespaces.
+/// Chars are utf-8 encoded chars (input is not validated).
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::adaptive_wc;
+///
+/// let text = "the café  is\topen\n".repeat(100_000);
+/// assert_eq!(
+///     adaptive_wc(text.as_bytes()),
+///     (100_000, 400_000, 1_900_000, 1_800_000)
+/// );
+/// ```
+pub fn adaptive_wc(bytes: &[u8]) -> (usize, usize, usize, usize) {
+    if bytes.is_empty() {
+        return (0, 0, 0, 0);
+    }
+    let count = bytes
+        .partial_fold(WordCount::default, |count, bytes, limit| {
+            let (todo, remaining) = bytes.divide_at(limit);
+            (count.fuse(WordCount::new(todo)), remaining)
+        })
+        .reduce(WordCount::fuse);
+    (count.lines, count.words, count.bytes, count.chars)
+}
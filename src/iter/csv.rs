@@ -0,0 +1,356 @@
+use crate::prelude::*;
+use crate::traits::BlockedPower;
+use std::borrow::Cow;
+use std::cmp::min;
+use std::fmt;
+use std::str::FromStr;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+
+/// Size of the chunks of text whose quotes parities are cached.
+const QUOTES_CHUNK_SIZE: usize = 4096;
+
+/// Why a field could not be parsed, with the position (in bytes) of the problem.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CsvError {
+    /// The record starting at this position has not enough fields.
+    MissingField(usize),
+    /// The field starting at this position could not be parsed.
+    InvalidField(usize),
+}
+
+impl CsvError {
+    pub fn position(self) -> usize {
+        match self {
+            CsvError::MissingField(position) | CsvError::InvalidField(position) => position,
+        }
+    }
+}
+
+impl fmt::Display for CsvError {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let problem = match self {
+            CsvError::MissingField(_) => "missing field",
+            CsvError::InvalidField(_) => "invalid field",
+        };
+        write!(f, "{} at position {}", problem, self.position())
+    }
+}
+
+impl std::error::Error for CsvError {}
+
+/// One record of a csv text.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CsvRecord<'a> {
+    position: usize,
+    // whole record, without its newline
+    line: &'a str,
+    fields: Vec<&'a str>,
+}
+
+impl<'a> CsvRecord<'a> {
+    /// Position (in bytes) of the record in the whole text.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+    /// All fields, without their enclosing quotes.
+    /// Escaped quotes are still doubled, see `field`.
+    pub fn fields(&self) -> &[&'a str] {
+        &self.fields
+    }
+    /// Return the field in given column, unescaped.
+    pub fn field(&self, column: usize) -> Option<Cow<'a, str>> {
+        self.fields.get(column).map(|&field| {
+            if field.contains('"') {
+                Cow::Owned(field.replace("\"\"", "\""))
+            } else {
+                Cow::Borrowed(field)
+            }
+        })
+    }
+    /// Position (in bytes) in the whole text of the field in given column.
+    fn field_position(&self, column: usize) -> usize {
+        self.position + (self.fields[column].as_ptr() as usize - self.line.as_ptr() as usize)
+    }
+    /// Parse the field in given column.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// use rayon_adaptive::CsvError;
+    /// let text = "id,price\n1,\"2.5\"\n2,free\n3\n";
+    /// let records: Vec<_> = text.adapt_csv_records().collect();
+    /// assert_eq!(records[1].parse::<f64>(1), Ok(2.5));
+    /// assert_eq!(records[2].parse::<f64>(1), Err(CsvError::InvalidField(19)));
+    /// assert_eq!(records[3].parse::<f64>(1), Err(CsvError::MissingField(24)));
+    /// ```
+    pub fn parse<T: FromStr>(&self, column: usize) -> Result<T, CsvError> {
+        let field = self
+            .field(column)
+            .ok_or(CsvError::MissingField(self.position))?;
+        field
+            .parse()
+            .map_err(|_| CsvError::InvalidField(self.field_position(column)))
+    }
+}
+
+/// Remove the quotes enclosing a field, if any.
+fn unquote(field: &str) -> &str {
+    if field.len() >= 2 && field.starts_with('"') && field.ends_with('"') {
+        &field[1..field.len() - 1]
+    } else {
+        field
+    }
+}
+
+fn count_quotes(bytes: &[u8]) -> usize {
+    bytes.iter().filter(|&&byte| byte == b'"').count()
+}
+
+/// Parities of the numbers of quotes in all chunks of a text, computed on demand.
+/// They are shared between all blocks such that each chunk is counted about once.
+struct QuotesParities {
+    // one bit per chunk
+    known: Vec<AtomicU64>,
+    odd: Vec<AtomicU64>,
+}
+
+impl QuotesParities {
+    fn new(text_length: usize) -> Self {
+        let words = text_length / QUOTES_CHUNK_SIZE / 64 + 1;
+        QuotesParities {
+            known: (0..words).map(|_| AtomicU64::new(0)).collect(),
+            odd: (0..words).map(|_| AtomicU64::new(0)).collect(),
+        }
+    }
+    /// Are we inside quotes at given position of the text ?
+    fn quoted_at(&self, bytes: &[u8], position: usize) -> bool {
+        let chunk = position / QUOTES_CHUNK_SIZE;
+        let mut odd_quotes = count_quotes(&bytes[chunk * QUOTES_CHUNK_SIZE..position]);
+        for word in 0..chunk.div_ceil(64) {
+            let mask = if (word + 1) * 64 <= chunk {
+                !0
+            } else {
+                (1 << (chunk % 64)) - 1
+            };
+            let mut unknown = !self.known[word].load(Ordering::Acquire) & mask;
+            while unknown != 0 {
+                let bit = unknown.trailing_zeros() as usize;
+                let start = (word * 64 + bit) * QUOTES_CHUNK_SIZE;
+                if count_quotes(&bytes[start..start + QUOTES_CHUNK_SIZE]) % 2 == 1 {
+                    self.odd[word].fetch_or(1 << bit, Ordering::Relaxed);
+                }
+                self.known[word].fetch_or(1 << bit, Ordering::Release);
+                unknown &= unknown - 1;
+            }
+            odd_quotes += (self.odd[word].load(Ordering::Relaxed) & mask).count_ones() as usize;
+        }
+        odd_quotes % 2 == 1
+    }
+}
+
+/// Adaptive iterator on records of a csv text.
+///
+/// Fields are separated by commas and records by newlines (with an optional `\r`).
+/// Fields can be quoted and contain commas, newlines and doubled quotes.
+/// Empty lines are skipped.
+///
+/// We divide on byte ranges and each block yields the records starting inside its range.
+/// A newline only ends a record when it is outside of quotes so each block
+/// also needs to know if it starts inside quotes.
+/// Counting quotes in the left part on each division would cost `O(n log(n))`
+/// so we only do it for small left parts. Other blocks find out when they are iterated,
+/// with quotes parities of chunks of the text shared between all blocks.
+pub struct AdaptiveCsvRecords<'a> {
+    text: &'a str,
+    start: usize,
+    end: usize,
+    // are we inside quotes at start, if known
+    quoted: Option<bool>,
+    parities: Arc<QuotesParities>,
+}
+
+impl<'a> AdaptiveCsvRecords<'a> {
+    pub(crate) fn new(text: &'a str) -> Self {
+        AdaptiveCsvRecords {
+            text,
+            start: 0,
+            end: text.len(),
+            quoted: Some(false),
+            parities: Arc::new(QuotesParities::new(text.len())),
+        }
+    }
+    /// Start of the first record starting in our range (or end).
+    fn first_record_start(&self) -> usize {
+        if self.start == 0 {
+            return 0;
+        }
+        let bytes = self.text.as_bytes();
+        let mut quoted = self
+            .quoted
+            .unwrap_or_else(|| self.parities.quoted_at(bytes, self.start));
+        // a newline does not change the quoting state
+        if bytes[self.start - 1] == b'\n' && !quoted {
+            return self.start;
+        }
+        bytes[self.start..self.end]
+            .iter()
+            .position(|&byte| {
+                if byte == b'"' {
+                    quoted = !quoted;
+                }
+                byte == b'\n' && !quoted
+            })
+            .map_or(self.end, |newline| self.start + newline + 1)
+    }
+    /// Parse the field in given column of all records.
+    /// On failure, return the first error in the text.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// use rayon_adaptive::CsvError;
+    /// let mut text = String::from("city,population\n");
+    /// for i in 0..100_000 {
+    ///     text.push_str(&format!("\"Saint-Martin, {}\",{}\n", i, 1000 + i));
+    /// }
+    /// let populations = text.adapt_csv_records().parse::<u64>(1);
+    /// assert_eq!(populations, Err(CsvError::InvalidField(5)));
+    /// let populations: Vec<u64> = text[16..].adapt_csv_records().parse(1).unwrap();
+    /// assert_eq!(populations, (1000..101_000).collect::<Vec<_>>());
+    /// ```
+    pub fn parse<T: FromStr + Send + Sync>(self, column: usize) -> Result<Vec<T>, CsvError> {
+        if self.base_length() == 0 {
+            return Ok(Vec::new());
+        }
+        self.partial_fold(
+            || Ok(Vec::new()),
+            |values, records, limit| {
+                let (todo, remaining) = records.divide_at(limit);
+                let values = values.and_then(|mut values| {
+                    for record in todo {
+                        values.push(record.parse(column)?);
+                    }
+                    Ok(values)
+                });
+                (values, remaining)
+            },
+        )
+        .reduce(|left, right| match (left, right) {
+            (Ok(mut left), Ok(right)) => {
+                left.extend(right);
+                Ok(left)
+            }
+            (Err(error), _) | (_, Err(error)) => Err(error),
+        })
+    }
+}
+
+impl<'a> Divisible for AdaptiveCsvRecords<'a> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        self.end - self.start
+    }
+    fn divide(self) -> (Self, Self) {
+        let middle = self.base_length() / 2;
+        self.divide_at(middle)
+    }
+}
+
+impl<'a> DivisibleIntoBlocks for AdaptiveCsvRecords<'a> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let middle = min(self.start + index, self.end);
+        // counting small parts is cheaper than looking up parities
+        let quoted = self
+            .quoted
+            .filter(|_| middle - self.start <= QUOTES_CHUNK_SIZE)
+            .map(|quoted| {
+                quoted ^ (count_quotes(&self.text.as_bytes()[self.start..middle]) % 2 == 1)
+            });
+        (
+            AdaptiveCsvRecords {
+                end: middle,
+                parities: self.parities.clone(),
+                ..self
+            },
+            AdaptiveCsvRecords {
+                start: middle,
+                quoted,
+                ..self
+            },
+        )
+    }
+}
+
+/// Sequential iterator on records of a csv text.
+pub struct CsvRecordsIter<'a> {
+    text: &'a str,
+    // start of next record
+    position: usize,
+    // we stop on the first record starting here or after
+    end: usize,
+}
+
+impl<'a> Iterator for CsvRecordsIter<'a> {
+    type Item = CsvRecord<'a>;
+    fn next(&mut self) -> Option<Self::Item> {
+        let bytes = self.text.as_bytes();
+        // skip empty lines
+        while self.position < self.end {
+            let remaining = &bytes[self.position..];
+            if remaining.starts_with(b"\n") {
+                self.position += 1;
+            } else if remaining.starts_with(b"\r\n") {
+                self.position += 2;
+            } else {
+                break;
+            }
+        }
+        if self.position >= self.end {
+            return None;
+        }
+        let start = self.position;
+        let mut fields = Vec::new();
+        let mut field_start = start;
+        let mut quoted = false;
+        let mut record_end = bytes.len();
+        for (index, &byte) in bytes.iter().enumerate().skip(start) {
+            match byte {
+                b'"' => quoted = !quoted,
+                b',' if !quoted => {
+                    fields.push(unquote(&self.text[field_start..index]));
+                    field_start = index + 1;
+                }
+                b'\n' if !quoted => {
+                    record_end = index;
+                    break;
+                }
+                _ => (),
+            }
+        }
+        self.position = min(record_end + 1, bytes.len());
+        let last_field = &self.text[field_start..record_end];
+        fields.push(unquote(last_field.strip_suffix('\r').unwrap_or(last_field)));
+        Some(CsvRecord {
+            position: start,
+            line: &self.text[start..record_end],
+            fields,
+        })
+    }
+}
+
+impl<'a> IntoIterator for AdaptiveCsvRecords<'a> {
+    type Item = CsvRecord<'a>;
+    type IntoIter = CsvRecordsIter<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        CsvRecordsIter {
+            text: self.text,
+            position: self.first_record_start(),
+            end: self.end,
+        }
+    }
+}
+
+impl<'a> AdaptiveIterator for AdaptiveCsvRecords<'a> {}
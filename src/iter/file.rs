@@ -0,0 +1,246 @@
+use crate::prelude::*;
+use crate::traits::BlockedPower;
+use std::cmp::{max, min};
+use std::fmt;
+use std::fs::File;
+use std::io;
+use std::ops::Deref;
+use std::os::unix::fs::FileExt;
+use std::sync::Arc;
+
+/// Maximal size of reads inside a block, bounding the memory used by each block.
+const MAX_READ_SIZE: usize = 1 << 20;
+/// Size of first read when completing the last line of a block.
+const TAIL_READ_SIZE: usize = 4096;
+
+/// Adaptive iterator on lines of a file, read with positional reads.
+///
+/// We divide on byte ranges, without any input-output.
+/// Each block yields the lines starting inside its range, so splits are snapped
+/// to the next newline only when the block is read.
+/// Nothing is read before iterating a block and blocks are read in buffers of bounded size
+/// so files larger than memory can be processed with any policy.
+///
+/// Lines are yielded without their final newline, like `BufRead::split`.
+/// They point into the buffers instead of being copied.
+///
+/// # Example
+///
+/// ```
+/// use rayon_adaptive::prelude::*;
+/// use rayon_adaptive::FileChunks;
+/// use std::fs::File;
+/// use std::io::Write;
+///
+/// let path = std::env::temp_dir().join("rayon_adaptive_file_chunks_example.log");
+/// let mut file = File::create(&path).unwrap();
+/// for i in 0..100_000 {
+///     writeln!(file, "{} {}", if i % 10 == 0 { "ERROR" } else { "INFO" }, i).unwrap();
+/// }
+/// let file = File::open(&path).unwrap();
+/// let errors = FileChunks::new(&file)
+///     .unwrap()
+///     .map(|line| line.unwrap())
+///     .filter(|line| line.starts_with(b"ERROR"))
+///     .fold(|| 0, |count, _| count + 1)
+///     .reduce(|count1, count2| count1 + count2);
+/// assert_eq!(errors, 10_000);
+/// std::fs::remove_file(&path).unwrap();
+/// ```
+pub struct FileChunks<'f> {
+    file: &'f File,
+    // size of the whole file
+    file_length: usize,
+    start: usize,
+    end: usize,
+}
+
+impl<'f> FileChunks<'f> {
+    /// Return an adaptive iterator on all lines of given file.
+    pub fn new(file: &'f File) -> io::Result<Self> {
+        let file_length = file.metadata()?.len() as usize;
+        Ok(FileChunks {
+            file,
+            file_length,
+            start: 0,
+            end: file_length,
+        })
+    }
+}
+
+impl<'f> Divisible for FileChunks<'f> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        self.end - self.start
+    }
+    fn divide(self) -> (Self, Self) {
+        let middle = self.base_length() / 2;
+        self.divide_at(middle)
+    }
+}
+
+impl<'f> DivisibleIntoBlocks for FileChunks<'f> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let middle = min(self.start + index, self.end);
+        (
+            FileChunks {
+                end: middle,
+                ..self
+            },
+            FileChunks {
+                start: middle,
+                ..self
+            },
+        )
+    }
+}
+
+/// A line of a file, sharing the buffer it was read into with other lines.
+#[derive(Clone)]
+pub struct FileLine {
+    buffer: Arc<Vec<u8>>,
+    start: usize,
+    end: usize,
+}
+
+impl Deref for FileLine {
+    type Target = [u8];
+    fn deref(&self) -> &[u8] {
+        &self.buffer[self.start..self.end]
+    }
+}
+
+impl AsRef<[u8]> for FileLine {
+    fn as_ref(&self) -> &[u8] {
+        self
+    }
+}
+
+impl fmt::Debug for FileLine {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        String::from_utf8_lossy(self).fmt(f)
+    }
+}
+
+/// Sequential iterator on lines of a block of a file.
+///
+/// We read in buffers of at most `MAX_READ_SIZE` bytes (more for longer lines)
+/// and lines point into them.
+pub struct FileLines<'f> {
+    file: &'f File,
+    file_length: usize,
+    // we stop on the first line starting here or after
+    end: usize,
+    // position in the file of the end of the buffer
+    read_position: usize,
+    buffer: Arc<Vec<u8>>,
+    // start of next line in the buffer
+    offset: usize,
+    error: Option<io::Error>,
+}
+
+impl<'f> FileLines<'f> {
+    /// Position in the file of the start of next line.
+    fn line_start(&self) -> usize {
+        self.read_position - self.buffer.len() + self.offset
+    }
+    /// Keep the remaining bytes of the buffer and read more after them.
+    fn read(&mut self) -> io::Result<()> {
+        let remaining = &self.buffer[self.offset..];
+        let wanted = if self.read_position < self.end {
+            min(self.end - self.read_position, MAX_READ_SIZE)
+        } else {
+            TAIL_READ_SIZE
+        };
+        // reading at least as much as we keep avoids quadratic copies on long lines
+        let read_size = min(
+            max(wanted, remaining.len()),
+            self.file_length - self.read_position,
+        );
+        let mut buffer = Vec::with_capacity(remaining.len() + read_size);
+        buffer.extend_from_slice(remaining);
+        buffer.resize(remaining.len() + read_size, 0);
+        self.file
+            .read_exact_at(&mut buffer[remaining.len()..], self.read_position as u64)?;
+        self.read_position += read_size;
+        self.buffer = Arc::new(buffer);
+        self.offset = 0;
+        Ok(())
+    }
+    /// Index in the buffer of the first newline after next line's start,
+    /// if it is before given position in the file. Read as much as needed.
+    fn find_newline(&mut self, limit: usize) -> io::Result<Option<usize>> {
+        let mut searched = self.offset;
+        loop {
+            if let Some(newline) = self.buffer[searched..].iter().position(|&b| b == b'\n') {
+                let index = searched + newline;
+                let newline_position = self.read_position - self.buffer.len() + index;
+                return Ok(Some(index).filter(|_| newline_position < limit));
+            }
+            if self.read_position >= min(limit, self.file_length) {
+                return Ok(None);
+            }
+            searched = self.buffer.len() - self.offset;
+            self.read()?;
+        }
+    }
+    /// Stop iterating after given error.
+    fn fail(&mut self, error: io::Error) -> io::Error {
+        self.end = 0;
+        error
+    }
+}
+
+impl<'f> Iterator for FileLines<'f> {
+    type Item = io::Result<FileLine>;
+    fn next(&mut self) -> Option<Self::Item> {
+        if let Some(error) = self.error.take() {
+            return Some(Err(self.fail(error)));
+        }
+        if self.line_start() >= self.end {
+            return None;
+        }
+        let line_end = match self.find_newline(self.file_length) {
+            Ok(Some(newline)) => newline,
+            // last line of the file, without newline
+            Ok(None) => self.buffer.len(),
+            Err(error) => return Some(Err(self.fail(error))),
+        };
+        let line = FileLine {
+            buffer: self.buffer.clone(),
+            start: self.offset,
+            end: line_end,
+        };
+        self.offset = min(line_end + 1, self.buffer.len());
+        Some(Ok(line))
+    }
+}
+
+impl<'f> IntoIterator for FileChunks<'f> {
+    type Item = io::Result<FileLine>;
+    type IntoIter = FileLines<'f>;
+    fn into_iter(self) -> Self::IntoIter {
+        // we also read the byte before us to know if a line starts on our first byte
+        let mut lines = FileLines {
+            file: self.file,
+            file_length: self.file_length,
+            end: self.end,
+            read_position: self.start.saturating_sub(1),
+            buffer: Arc::new(Vec::new()),
+            offset: 0,
+            error: None,
+        };
+        if self.start != 0 {
+            // skip the end of the previous line,
+            // a newline on our last byte starts a line which is not ours
+            match lines.find_newline(self.end - 1) {
+                Ok(Some(newline)) => lines.offset = newline + 1,
+                Ok(None) => lines.end = 0,
+                Err(error) => lines.error = Some(error),
+            }
+        }
+        lines
+    }
+}
+
+impl<'f> AdaptiveIterator for FileChunks<'f> {}
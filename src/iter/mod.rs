@@ -14,17 +14,27 @@ mod cloned;
 use self::cloned::Cloned;
 mod filter;
 use self::filter::Filter;
-use crate::policy::ParametrizedInput;
+use crate::policy::{ParametrizedInput, Policy};
 use std;
-use std::cmp::min;
+use std::cmp::{max, min};
 mod collect;
 pub use self::collect::{FromAdaptiveBlockedIterator, FromAdaptiveIndexedIterator};
+pub(crate) mod csv;
+#[cfg(unix)]
+pub(crate) mod file;
 pub(crate) mod hash;
 pub(crate) mod str;
 use crate::utils::powers;
 use crate::utils::AbortingDivisible;
+use rayon::current_num_threads;
+use std::fmt::Display;
+use std::io::{self, Write};
+use std::iter::repeat;
 use std::sync::atomic::{AtomicBool, Ordering};
 
+/// Number of input elements per thread in each macro-block when writing.
+const WRITE_BLOCK_SIZE: usize = 1 << 16;
+
 pub trait IntoAdaptiveIterator: IntoIterator + DivisibleIntoBlocks {
     fn into_adapt_iter(self) -> Iter<Self> {
         Iter { input: self }
@@ -129,7 +139,7 @@ pub trait AdaptiveIteratorRunner<I: AdaptiveIterator, S: Iterator<Item = usize>>
     {
         let (input, policy, sizes) = self.input_policy_sizes();
         let len = input.base_length();
-        let base_size = min((len as f64).log(2.0).ceil() as usize, len);
+        let base_size = min(max((len as f64).log(2.0).ceil() as usize, 1), len);
         input
             .with_policy(policy)
             .by_blocks(sizes.chain(powers(base_size)))
@@ -158,7 +168,8 @@ pub trait AdaptiveIteratorRunner<I: AdaptiveIterator, S: Iterator<Item = usize>>
     ///
     /// ```
     /// use rayon_adaptive::prelude::*;
-    /// assert!((0..10_000).into_adapt_iter().any(|x| x == 2345))
+    /// assert!((0..10_000).into_adapt_iter().any(|x| x == 2345));
+    /// assert!((0..2).into_adapt_iter().any(|x| x == 1));
     /// ```
     fn any<P>(self, predicate: P) -> bool
     where
@@ -185,10 +196,8 @@ pub trait AdaptiveIteratorRunner<I: AdaptiveIterator, S: Iterator<Item = usize>>
         P: Fn(I::Item) -> bool + Sync + Send,
     {
         let (input, policy, sizes) = self.input_policy_sizes();
-        let base_size = std::cmp::min(
-            (input.base_length() as f64).log(2.0).ceil() as usize,
-            input.base_length(),
-        );
+        let len = input.base_length();
+        let base_size = min(max((len as f64).log(2.0).ceil() as usize, 1), len);
         ActivatedInput {
             input,
             folder: Fold {
@@ -338,6 +347,91 @@ pub trait AdaptiveIteratorRunner<I: AdaptiveIterator, S: Iterator<Item = usize>>
             power: PhantomData,
         }
     }
+
+    /// Write all items (as bytes) into given writer, in order.
+    ///
+    /// Items are serialized in parallel by macro-blocks, then written
+    /// sequentially before the next macro-block starts.
+    /// Memory is therefore bounded by the serialized size of one macro-block.
+    /// Macro-blocks sizes can be chosen with `by_blocks`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let mut output = Vec::new();
+    /// (0..100_000)
+    ///     .into_adapt_iter()
+    ///     .map(|i| (i as u32).to_le_bytes())
+    ///     .write_ordered(&mut output)
+    ///     .unwrap();
+    /// assert_eq!(output.len(), 400_000);
+    /// assert_eq!(&output[400..404], &100u32.to_le_bytes());
+    /// ```
+    fn write_ordered<W: Write>(self, writer: W) -> io::Result<()>
+    where
+        I::Item: AsRef<[u8]>,
+    {
+        let (input, policy, sizes) = self.input_policy_sizes();
+        write_blocks(input, policy, sizes, writer, |buffer, item| {
+            buffer.extend_from_slice(item.as_ref())
+        })
+    }
+
+    /// Write all items into given writer, in order, one per line.
+    /// See `write_ordered`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let mut output = Vec::new();
+    /// (0..100_000)
+    ///     .into_adapt_iter()
+    ///     .filter(|i| i % 3 == 0)
+    ///     .write_ordered_lines(&mut output)
+    ///     .unwrap();
+    /// let output = String::from_utf8(output).unwrap();
+    /// assert!(output.starts_with("0\n3\n6\n"));
+    /// assert_eq!(output.lines().count(), 33_334);
+    /// ```
+    fn write_ordered_lines<W: Write>(self, writer: W) -> io::Result<()>
+    where
+        I::Item: Display,
+    {
+        let (input, policy, sizes) = self.input_policy_sizes();
+        write_blocks(input, policy, sizes, writer, |buffer, item| {
+            writeln!(buffer, "{}", item).expect("writing in memory failed")
+        })
+    }
+}
+
+/// Serialize items into buffers by macro-blocks and write buffers in order.
+fn write_blocks<I, S, W, F>(
+    input: I,
+    policy: Policy,
+    sizes: S,
+    mut writer: W,
+    serialize: F,
+) -> io::Result<()>
+where
+    I: AdaptiveIterator,
+    S: Iterator<Item = usize>,
+    W: Write,
+    F: Fn(&mut Vec<u8>, I::Item) + Sync,
+{
+    input
+        .with_policy(policy)
+        .by_blocks(sizes.chain(repeat(WRITE_BLOCK_SIZE * current_num_threads())))
+        .partial_fold(Vec::new, |mut buffer, i, limit| {
+            let (todo, remaining) = i.divide_at(limit);
+            for item in todo {
+                serialize(&mut buffer, item);
+            }
+            (buffer, remaining)
+        })
+        .into_iter()
+        .try_for_each(|buffer| writer.write_all(&buffer))
 }
 
 /// Specializations of AdaptiveIteratorRunner.
@@ -1,7 +1,12 @@
+use crate::algorithms::substring::{adaptive_find_all, adaptive_find_first};
+use crate::algorithms::wc::adaptive_wc;
+use crate::iter::csv::AdaptiveCsvRecords;
+use crate::iter::{cloned::Cloned, iter::Iter};
 use crate::prelude::*;
 use crate::traits::BlockedPower;
-use itertools::Itertools;
-use std::str::Chars;
+use itertools::{Either, Itertools};
+use std::cmp::min;
+use std::str::{CharIndices, Chars, Lines, Split, SplitTerminator, SplitWhitespace};
 
 /// Adaptive iterator on characters of strings.
 pub struct AdaptiveChars<'a> {
@@ -18,20 +23,122 @@ impl<'a> IntoIterator for AdaptiveChars<'a> {
 
 impl<'a> AdaptiveChars<'a> {
     fn find_splitting_index_around(&self, start_index: usize) -> usize {
-        let len = self.real_str.len();
-        let higher_indices = start_index..len;
-        let lower_indices = (0..start_index).rev();
-        lower_indices
-            .interleave(higher_indices)
-            .find(|&i| self.real_str.is_char_boundary(i))
-            .unwrap_or(len) // if we cannot split we just generate an empty slice
+        find_splitting_index_around(self.real_str, start_index)
+    }
+}
+
+/// Length of a string as a divisible, a single char cannot be divided.
+fn char_string_length(real_str: &str) -> usize {
+    if real_str.chars().nth(1).is_none() {
+        min(real_str.len(), 1)
+    } else {
+        real_str.len()
+    }
+}
+
+fn find_splitting_index_around(real_str: &str, start_index: usize) -> usize {
+    let len = real_str.len();
+    let higher_indices = start_index..=len;
+    // splitting at 0 would not make any progress
+    let lower_indices = (1..start_index).rev();
+    higher_indices
+        .interleave(lower_indices)
+        .find(|&i| real_str.is_char_boundary(i))
+        .unwrap_or(len) // if we cannot split we just generate an empty slice
+}
+
+/// Return the first index not below `start_index` which is 0 or just after a delimiter.
+fn delimiter_end_after<D: Fn(char) -> bool>(
+    real_str: &str,
+    start_index: usize,
+    is_delimiter: &D,
+) -> Option<usize> {
+    let start_index = min(start_index, real_str.len());
+    if start_index == 0 {
+        return Some(0);
+    }
+    // start of the char containing the byte before start_index
+    let mut index = start_index - 1;
+    while !real_str.is_char_boundary(index) {
+        index -= 1;
+    }
+    real_str[index..]
+        .char_indices()
+        .find(|&(_, c)| is_delimiter(c))
+        .map(|(i, c)| index + i + c.len_utf8())
+}
+
+/// Part of a string we only divide just after delimiters.
+struct Delimited<'a> {
+    real_str: &'a str,
+    // no possible division point, only happens when created by `divide`
+    indivisible: bool,
+}
+
+impl<'a> Delimited<'a> {
+    fn base_length(&self) -> usize {
+        if self.indivisible {
+            min(self.real_str.len(), 1)
+        } else {
+            self.real_str.len()
+        }
+    }
+    /// Divide just after a delimiter close to the middle.
+    fn divide<D: Fn(char) -> bool>(self, is_delimiter: D) -> (Self, Self) {
+        let real_str = self.real_str;
+        let len = real_str.len();
+        let middle = delimiter_end_after(real_str, len / 2, &is_delimiter)
+            .filter(|&index| index > 0 && index < len)
+            .or_else(|| {
+                let mut index = len / 2;
+                while !real_str.is_char_boundary(index) {
+                    index -= 1;
+                }
+                real_str[..index]
+                    .char_indices()
+                    .rev()
+                    .find(|&(_, c)| is_delimiter(c))
+                    .map(|(i, c)| i + c.len_utf8())
+            });
+        match middle {
+            Some(middle) => self.split_at(middle),
+            None => (
+                Delimited {
+                    indivisible: true,
+                    ..self
+                },
+                Delimited {
+                    real_str: &real_str[len..],
+                    indivisible: false,
+                },
+            ),
+        }
+    }
+    /// Divide just after the first delimiter following `index`.
+    fn divide_at<D: Fn(char) -> bool>(self, index: usize, is_delimiter: D) -> (Self, Self) {
+        let index =
+            delimiter_end_after(self.real_str, index, &is_delimiter).unwrap_or(self.real_str.len());
+        self.split_at(index)
+    }
+    fn split_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = self.real_str.split_at(index);
+        (
+            Delimited {
+                real_str: left,
+                indivisible: self.indivisible,
+            },
+            Delimited {
+                real_str: right,
+                indivisible: false,
+            },
+        )
     }
 }
 
 impl<'a> Divisible for AdaptiveChars<'a> {
     type Power = BlockedPower;
     fn base_length(&self) -> usize {
-        self.real_str.len()
+        char_string_length(self.real_str)
     }
     /// Pre-condition: self.can_be_divided() is true.
     fn divide(self) -> (Self, Self) {
@@ -58,12 +165,401 @@ impl<'a> DivisibleIntoBlocks for AdaptiveChars<'a> {
 
 impl<'a> AdaptiveIterator for AdaptiveChars<'a> {}
 
+/// Adaptive iterator on characters of strings and their byte indices.
+pub struct AdaptiveCharIndices<'a> {
+    real_str: &'a str,
+    // index of our start in the whole string
+    offset: usize,
+}
+
+/// Sequential iterator on characters of a part of a string and their byte indices.
+pub struct CharIndicesIter<'a> {
+    char_indices: CharIndices<'a>,
+    offset: usize,
+}
+
+impl<'a> Iterator for CharIndicesIter<'a> {
+    type Item = (usize, char);
+    fn next(&mut self) -> Option<Self::Item> {
+        let offset = self.offset;
+        self.char_indices.next().map(|(i, c)| (offset + i, c))
+    }
+}
+
+impl<'a> IntoIterator for AdaptiveCharIndices<'a> {
+    type Item = (usize, char);
+    type IntoIter = CharIndicesIter<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        CharIndicesIter {
+            char_indices: self.real_str.char_indices(),
+            offset: self.offset,
+        }
+    }
+}
+
+impl<'a> Divisible for AdaptiveCharIndices<'a> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        char_string_length(self.real_str)
+    }
+    fn divide(self) -> (Self, Self) {
+        let middle = self.real_str.len() / 2;
+        self.divide_at(middle)
+    }
+}
+
+impl<'a> DivisibleIntoBlocks for AdaptiveCharIndices<'a> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let index = find_splitting_index_around(self.real_str, index);
+        let (left, right) = self.real_str.split_at(index);
+        (
+            AdaptiveCharIndices {
+                real_str: left,
+                offset: self.offset,
+            },
+            AdaptiveCharIndices {
+                real_str: right,
+                offset: self.offset + index,
+            },
+        )
+    }
+}
+
+impl<'a> AdaptiveIterator for AdaptiveCharIndices<'a> {}
+
+/// Adaptive iterator on lines of strings.
+/// We only divide after newlines.
+pub struct AdaptiveLines<'a> {
+    text: Delimited<'a>,
+}
+
+fn is_newline(c: char) -> bool {
+    c == '\n'
+}
+
+impl<'a> IntoIterator for AdaptiveLines<'a> {
+    type Item = &'a str;
+    type IntoIter = Lines<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.text.real_str.lines()
+    }
+}
+
+impl<'a> Divisible for AdaptiveLines<'a> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        self.text.base_length()
+    }
+    fn divide(self) -> (Self, Self) {
+        let (left, right) = self.text.divide(is_newline);
+        (AdaptiveLines { text: left }, AdaptiveLines { text: right })
+    }
+}
+
+impl<'a> DivisibleIntoBlocks for AdaptiveLines<'a> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = self.text.divide_at(index, is_newline);
+        (AdaptiveLines { text: left }, AdaptiveLines { text: right })
+    }
+}
+
+impl<'a> AdaptiveIterator for AdaptiveLines<'a> {}
+
+/// Adaptive iterator on substrings separated by a character.
+/// We only divide after separators.
+pub struct AdaptiveSplit<'a> {
+    text: Delimited<'a>,
+    separator: char,
+    // are we ending the whole string ? if not we end with a separator (or are empty)
+    last: bool,
+}
+
+impl<'a> AdaptiveSplit<'a> {
+    /// Parts of the string ending with us after a division.
+    fn from_parts(
+        (left, right): (Delimited<'a>, Delimited<'a>),
+        separator: char,
+        last: bool,
+    ) -> (Self, Self) {
+        // empty parts are never iterated so we keep the last empty string on the left
+        let left_is_last = last && right.real_str.is_empty();
+        (
+            AdaptiveSplit {
+                text: left,
+                separator,
+                last: left_is_last,
+            },
+            AdaptiveSplit {
+                text: right,
+                separator,
+                last: last && !left_is_last,
+            },
+        )
+    }
+}
+
+impl<'a> IntoIterator for AdaptiveSplit<'a> {
+    type Item = &'a str;
+    type IntoIter = Either<Split<'a, char>, SplitTerminator<'a, char>>;
+    fn into_iter(self) -> Self::IntoIter {
+        if self.last {
+            Either::Left(self.text.real_str.split(self.separator))
+        } else {
+            // the empty string after our final separator is not ours
+            Either::Right(self.text.real_str.split_terminator(self.separator))
+        }
+    }
+}
+
+impl<'a> Divisible for AdaptiveSplit<'a> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        // the empty string ending an empty text still needs to be iterated
+        if self.last && self.text.real_str.is_empty() {
+            1
+        } else {
+            self.text.base_length()
+        }
+    }
+    fn divide(self) -> (Self, Self) {
+        let AdaptiveSplit {
+            text,
+            separator,
+            last,
+        } = self;
+        AdaptiveSplit::from_parts(text.divide(|c| c == separator), separator, last)
+    }
+}
+
+impl<'a> DivisibleIntoBlocks for AdaptiveSplit<'a> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let AdaptiveSplit {
+            text,
+            separator,
+            last,
+        } = self;
+        AdaptiveSplit::from_parts(text.divide_at(index, |c| c == separator), separator, last)
+    }
+}
+
+impl<'a> AdaptiveIterator for AdaptiveSplit<'a> {}
+
+/// Adaptive iterator on non-whitespace substrings.
+/// We only divide after whitespaces.
+pub struct AdaptiveSplitWhitespace<'a> {
+    text: Delimited<'a>,
+}
+
+impl<'a> IntoIterator for AdaptiveSplitWhitespace<'a> {
+    type Item = &'a str;
+    type IntoIter = SplitWhitespace<'a>;
+    fn into_iter(self) -> Self::IntoIter {
+        self.text.real_str.split_whitespace()
+    }
+}
+
+impl<'a> Divisible for AdaptiveSplitWhitespace<'a> {
+    type Power = BlockedPower;
+    fn base_length(&self) -> usize {
+        self.text.base_length()
+    }
+    fn divide(self) -> (Self, Self) {
+        let (left, right) = self.text.divide(char::is_whitespace);
+        (
+            AdaptiveSplitWhitespace { text: left },
+            AdaptiveSplitWhitespace { text: right },
+        )
+    }
+}
+
+impl<'a> DivisibleIntoBlocks for AdaptiveSplitWhitespace<'a> {
+    fn divide_at(self, index: usize) -> (Self, Self) {
+        let (left, right) = self.text.divide_at(index, char::is_whitespace);
+        (
+            AdaptiveSplitWhitespace { text: left },
+            AdaptiveSplitWhitespace { text: right },
+        )
+    }
+}
+
+impl<'a> AdaptiveIterator for AdaptiveSplitWhitespace<'a> {}
+
 pub trait AdaptiveString {
     fn adapt_chars(&self) -> AdaptiveChars;
+    /// Return an adaptive iterator on characters and their byte indices in the whole string.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "aé€".repeat(10_000);
+    /// let euros: Vec<usize> = text
+    ///     .adapt_char_indices()
+    ///     .filter(|&(_, c)| c == '€')
+    ///     .map(|(i, _)| i)
+    ///     .collect();
+    /// assert_eq!(euros.len(), 10_000);
+    /// assert!(euros.iter().enumerate().all(|(k, &i)| i == 6 * k + 3));
+    /// ```
+    fn adapt_char_indices(&self) -> AdaptiveCharIndices;
+    /// Return an adaptive iterator on bytes.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "aé€".repeat(10_000);
+    /// let continuation_bytes = text.adapt_bytes().filter(|b| b & 0xC0 == 0x80).count();
+    /// assert_eq!(continuation_bytes, 30_000);
+    /// ```
+    fn adapt_bytes(&self) -> Cloned<Iter<&[u8]>>;
+    /// Return an adaptive iterator on lines, like `lines`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "GET /\r\nPOST /login\n\nGET /img\n".repeat(10_000);
+    /// let lines: Vec<&str> = text.adapt_lines().collect();
+    /// assert_eq!(lines, text.lines().collect::<Vec<_>>());
+    /// assert_eq!(text.adapt_lines().filter(|l| l.starts_with("GET")).count(), 20_000);
+    /// ```
+    fn adapt_lines(&self) -> AdaptiveLines;
+    /// Return an adaptive iterator on substrings separated by `separator`, like `split`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "a,,bb,".repeat(10_000);
+    /// let fields: Vec<&str> = text.adapt_split(',').collect();
+    /// assert_eq!(fields, text.split(',').collect::<Vec<_>>());
+    /// assert_eq!("".adapt_split(',').collect::<Vec<_>>(), vec![""]);
+    /// ```
+    fn adapt_split(&self, separator: char) -> AdaptiveSplit;
+    /// Return an adaptive iterator on substrings separated by whitespaces, like `split_whitespace`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "the quick  brown\tfox\n".repeat(10_000);
+    /// let words: Vec<&str> = text.adapt_split_whitespace().collect();
+    /// assert_eq!(words, text.split_whitespace().collect::<Vec<_>>());
+    /// ```
+    fn adapt_split_whitespace(&self) -> AdaptiveSplitWhitespace;
+    /// Return the byte indices of all (possibly overlapping) occurrences of `pattern`.
+    /// Unlike `match_indices` overlapping occurrences are all reported.
+    /// An empty pattern occurs at all char boundaries.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "héhé, hého".repeat(1000);
+    /// let positions = text.adapt_find_all("hé");
+    /// assert_eq!(positions.len(), 3000);
+    /// assert_eq!(&positions[..3], &[0, 3, 8]);
+    /// assert_eq!("hé".adapt_find_all(""), vec![0, 1, 3]);
+    /// ```
+    fn adapt_find_all(&self, pattern: &str) -> Vec<usize>;
+    /// Return the byte index of the first occurrence of `pattern`, if any.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "héhé, hého".repeat(1000);
+    /// assert_eq!(text.adapt_find_first("hého"), Some(8));
+    /// assert_eq!(text.adapt_find_first("hoho"), None);
+    /// ```
+    fn adapt_find_first(&self, pattern: &str) -> Option<usize>;
+    /// Return the numbers of lines, words, bytes and chars like `wc`.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "the café  is\topen\n".repeat(100_000);
+    /// assert_eq!(text.adapt_wc(), (100_000, 400_000, 1_900_000, 1_800_000));
+    /// ```
+    fn adapt_wc(&self) -> (usize, usize, usize, usize);
+    /// Return an adaptive iterator on records of a csv text.
+    /// Quoted fields can contain commas and newlines.
+    ///
+    /// # Example
+    ///
+    /// ```
+    /// use rayon_adaptive::prelude::*;
+    /// let text = "name,comment\nbob,\"hello,\nworld\"\nalice,\"say \"\"hi\"\"\"\n".repeat(10_000);
+    /// let records: Vec<_> = text.adapt_csv_records().collect();
+    /// assert_eq!(records.len(), 30_000);
+    /// assert_eq!(records[1].fields(), &["bob", "hello,\nworld"]);
+    /// assert_eq!(records[2].field(1).unwrap(), "say \"hi\"");
+    /// assert_eq!(records[3].position(), text.len() / 10_000);
+    /// ```
+    fn adapt_csv_records(&self) -> AdaptiveCsvRecords;
 }
 
 impl AdaptiveString for str {
     fn adapt_chars(&self) -> AdaptiveChars {
         AdaptiveChars { real_str: self }
     }
+    fn adapt_char_indices(&self) -> AdaptiveCharIndices {
+        AdaptiveCharIndices {
+            real_str: self,
+            offset: 0,
+        }
+    }
+    fn adapt_bytes(&self) -> Cloned<Iter<&[u8]>> {
+        self.as_bytes().into_adapt_iter().cloned()
+    }
+    fn adapt_lines(&self) -> AdaptiveLines {
+        AdaptiveLines {
+            text: Delimited {
+                real_str: self,
+                indivisible: false,
+            },
+        }
+    }
+    fn adapt_split(&self, separator: char) -> AdaptiveSplit {
+        AdaptiveSplit {
+            text: Delimited {
+                real_str: self,
+                indivisible: false,
+            },
+            separator,
+            last: true,
+        }
+    }
+    fn adapt_split_whitespace(&self) -> AdaptiveSplitWhitespace {
+        AdaptiveSplitWhitespace {
+            text: Delimited {
+                real_str: self,
+                indivisible: false,
+            },
+        }
+    }
+    fn adapt_find_all(&self, pattern: &str) -> Vec<usize> {
+        if pattern.is_empty() {
+            let mut boundaries: Vec<usize> = if self.is_empty() {
+                Vec::new()
+            } else {
+                self.adapt_char_indices().map(|(i, _)| i).collect()
+            };
+            boundaries.push(self.len());
+            return boundaries;
+        }
+        // utf-8 is self-synchronizing : all byte occurrences start on char boundaries
+        adaptive_find_all(self.as_bytes(), pattern.as_bytes())
+    }
+    fn adapt_find_first(&self, pattern: &str) -> Option<usize> {
+        adaptive_find_first(self.as_bytes(), pattern.as_bytes())
+    }
+    fn adapt_wc(&self) -> (usize, usize, usize, usize) {
+        adaptive_wc(self.as_bytes())
+    }
+    fn adapt_csv_records(&self) -> AdaptiveCsvRecords {
+        AdaptiveCsvRecords::new(self)
+    }
 }
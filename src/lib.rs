@@ -21,6 +21,9 @@ pub use crate::slices::{EdibleSlice, EdibleSliceMut};
 mod activated_input;
 mod chunks;
 pub mod iter;
+pub use crate::iter::csv::{AdaptiveCsvRecords, CsvError, CsvRecord, CsvRecordsIter};
+#[cfg(unix)]
+pub use crate::iter::file::{FileChunks, FileLine, FileLines};
 pub use crate::iter::hash::{par_elements, par_iter, par_keys};
 pub use crate::iter::iter::Iter;
 pub use crate::iter::map::Map;
@@ -36,9 +39,37 @@ mod smallchannel;
 pub use crate::smallchannel::{small_channel, SmallReceiver, SmallSender};
 
 mod algorithms;
+pub use crate::algorithms::batch_search::adaptive_batch_search;
+pub use crate::algorithms::expression::{
+    evaluate_adaptive, evaluate_fully_adaptive, evaluate_seq, ExpressionError, Modular, Number,
+};
+pub use crate::algorithms::fsm::{adaptive_fsm_final_state, adaptive_fsm_run};
+pub use crate::algorithms::group_by::{adaptive_reduce_by_key, group_by_fold};
+pub use crate::algorithms::histogram::adaptive_histogram;
 pub use crate::algorithms::infix_solvers::*;
-pub use crate::algorithms::prefix::{adaptive_prefix, fully_adaptive_prefix};
-pub use crate::algorithms::{merge_sort::adaptive_sort, merge_sort_raw::adaptive_sort_raw};
+pub use crate::algorithms::join::{adaptive_hash_join, adaptive_sort_merge_join};
+pub use crate::algorithms::kway_merge::adaptive_kway_merge;
+pub use crate::algorithms::linear_recurrence::adaptive_linear_recurrence;
+pub use crate::algorithms::merge_sort::{
+    adaptive_merge, adaptive_merge_by, adaptive_sort, adaptive_sort_by, adaptive_sort_by_key,
+};
+pub use crate::algorithms::merge_sort_raw::adaptive_sort_raw;
+pub use crate::algorithms::prefix::{adaptive_prefix, adaptive_prefix_2d, fully_adaptive_prefix};
+pub use crate::algorithms::quick_sort::{adaptive_sort_unstable, adaptive_sort_unstable_by};
+pub use crate::algorithms::radix_sort::{
+    adaptive_radix_sort, adaptive_radix_sort_by_key, RadixKey,
+};
+pub use crate::algorithms::retain::{adaptive_dedup, adaptive_partition_in_place, adaptive_retain};
+pub use crate::algorithms::scans::{
+    adaptive_bracket_balance, adaptive_longest_run, adaptive_longest_run_by, adaptive_max_subarray,
+};
+pub use crate::algorithms::selection::{adaptive_select_nth, adaptive_top_k};
+pub use crate::algorithms::sorted_sets::{
+    adaptive_difference, adaptive_intersection, adaptive_symmetric_difference, adaptive_union,
+};
+pub use crate::algorithms::substring::{adaptive_find_all, adaptive_find_first};
+pub use crate::algorithms::utf8::adaptive_from_utf8;
+pub use crate::algorithms::wc::adaptive_wc;
 
 /// Execute potentially `oper_a` and `oper_b` in parallel like in a standard join.
 /// Then the last closure to finish calls `oper_c` on both results.
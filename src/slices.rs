@@ -133,6 +133,12 @@ impl<'a, T: 'a> EdibleSliceMut<'a, T> {
     pub fn new(slice: &'a mut [T]) -> Self {
         EdibleSliceMut { slice, used: 0 }
     }
+    /// Create a new `EdibleSliceMut` out of given mutable slice,
+    /// with the first `used` elements already used.
+    pub(crate) fn with_used(slice: &'a mut [T], used: usize) -> Self {
+        assert!(used <= slice.len());
+        EdibleSliceMut { slice, used }
+    }
     /// Take a look at next element.
     pub fn peek(&mut self) -> Option<&mut T> {
         self.slice.get_mut(self.used)